@@ -2,6 +2,11 @@ pub use sea_orm_migration::prelude::*;
 
 mod m20230306_145109_create_initial_schema;
 mod m20230322_211054_add_pr_link;
+mod m20261019_090000_create_repository_snapshot;
+mod m20261019_100000_create_pipeline_status_transition;
+mod m20261019_110000_create_alert;
+mod m20261019_120000_create_webhook_delivery;
+mod m20261019_130000_create_pipeline_run;
 
 pub struct Migrator;
 
@@ -11,6 +16,11 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20230306_145109_create_initial_schema::Migration),
             Box::new(m20230322_211054_add_pr_link::Migration),
+            Box::new(m20261019_090000_create_repository_snapshot::Migration),
+            Box::new(m20261019_100000_create_pipeline_status_transition::Migration),
+            Box::new(m20261019_110000_create_alert::Migration),
+            Box::new(m20261019_120000_create_webhook_delivery::Migration),
+            Box::new(m20261019_130000_create_pipeline_run::Migration),
         ]
     }
 }
@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RepositorySnapshot::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(RepositorySnapshot::RepositoryName)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(RepositorySnapshot::Position)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(RepositorySnapshot::LastUpdatedDate)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(RepositorySnapshot::Data).text().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(RepositorySnapshot::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum RepositorySnapshot {
+    Table,
+    RepositoryName,
+    Position,
+    LastUpdatedDate,
+    Data,
+}
@@ -0,0 +1,77 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PipelineStatusTransition::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PipelineStatusTransition::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PipelineStatusTransition::Repository)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PipelineStatusTransition::Branch)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(
+                        ColumnDef::new(PipelineStatusTransition::Status)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PipelineStatusTransition::PipelineUrl).text())
+                    .col(
+                        ColumnDef::new(PipelineStatusTransition::Timestamp)
+                            .string()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_pipeline_status_transition_repository_branch")
+                    .table(PipelineStatusTransition::Table)
+                    .col(PipelineStatusTransition::Repository)
+                    .col(PipelineStatusTransition::Branch)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(PipelineStatusTransition::Table)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum PipelineStatusTransition {
+    Table,
+    Id,
+    Repository,
+    Branch,
+    Status,
+    PipelineUrl,
+    Timestamp,
+}
@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Alert::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Alert::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Alert::Rule).string().not_null())
+                    .col(ColumnDef::new(Alert::Repository).string().not_null())
+                    .col(ColumnDef::new(Alert::Subject).string().not_null())
+                    .col(ColumnDef::new(Alert::Message).text().not_null())
+                    .col(ColumnDef::new(Alert::Link).text())
+                    .col(ColumnDef::new(Alert::RaisedAt).string().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Alert::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Alert {
+    Table,
+    Id,
+    Rule,
+    Repository,
+    Subject,
+    Message,
+    Link,
+    RaisedAt,
+}
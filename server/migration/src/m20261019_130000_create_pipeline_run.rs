@@ -0,0 +1,71 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PipelineRun::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(PipelineRun::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(PipelineRun::Repository).string().not_null())
+                    .col(ColumnDef::new(PipelineRun::Branch).string().not_null())
+                    .col(ColumnDef::new(PipelineRun::CommitSha).string().not_null())
+                    .col(ColumnDef::new(PipelineRun::Status).string().not_null())
+                    .col(ColumnDef::new(PipelineRun::PipelineUrl).text())
+                    .col(ColumnDef::new(PipelineRun::StartedDate).string())
+                    .col(
+                        ColumnDef::new(PipelineRun::FinishedDate)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(PipelineRun::DurationSeconds).big_integer())
+                    .to_owned(),
+            )
+            .await?;
+        // a run is seen on every refresh until the branch gets a new pipeline
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_pipeline_run_unique")
+                    .table(PipelineRun::Table)
+                    .col(PipelineRun::Repository)
+                    .col(PipelineRun::Branch)
+                    .col(PipelineRun::CommitSha)
+                    .col(PipelineRun::FinishedDate)
+                    .unique()
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(PipelineRun::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum PipelineRun {
+    Table,
+    Id,
+    Repository,
+    Branch,
+    CommitSha,
+    Status,
+    PipelineUrl,
+    StartedDate,
+    FinishedDate,
+    DurationSeconds,
+}
@@ -1,8 +1,17 @@
+use std::sync::Mutex;
+
 use anyhow::{anyhow, Context};
 use reqwest::Method;
 
-use crate::adapter::bitbucket::repositories::request::load_repositories_data;
-use crate::service::repositories::model::{RepositoriesData, Repository};
+use crate::adapter::bitbucket::repositories::request::{
+    discover_project_repositories, load_repositories_data,
+};
+use crate::config::RepositoryDiscoveryConfiguration;
+use crate::service::repositories::branch_filter::BranchFilters;
+use crate::service::repositories::cache::AheadBehindCache;
+use crate::service::repositories::model::{
+    merge_repositories, RepositoriesData, Repository, RepositorySelector,
+};
 
 mod model;
 mod request;
@@ -12,22 +21,43 @@ pub struct BitbucketClient {
     url: String,
     user: String,
     password: String,
-    repositories: Vec<Repository>,
+    repositories: Vec<RepositorySelector>,
+    discovery: RepositoryDiscoveryConfiguration,
+    branch_filters: BranchFilters,
+    ahead_behind_cache: Mutex<AheadBehindCache>,
 }
 
 impl BitbucketClient {
     pub fn new(
-        repositories: &[Repository],
+        repositories: &[RepositorySelector],
         url: String,
         user: String,
         password: String,
+        discovery: RepositoryDiscoveryConfiguration,
+        branch_filters: BranchFilters,
     ) -> anyhow::Result<BitbucketClient> {
+        // repositories are always directly in a project, which cannot be nested
+        for selector in repositories {
+            let namespace = match selector {
+                RepositorySelector::Repository(repository) => &repository.namespace,
+                RepositorySelector::Namespace { namespace, .. } => namespace,
+            };
+            if namespace.contains('/') {
+                return Err(anyhow!(
+                    "Invalid Bitbucket project {namespace}, repositories must be given as PROJECT/repo."
+                ));
+            }
+        }
+
         Ok(BitbucketClient {
             client: reqwest::Client::new(),
             url,
             user,
             password,
             repositories: Vec::from(repositories),
+            discovery,
+            branch_filters,
+            ahead_behind_cache: Mutex::new(AheadBehindCache::default()),
         })
     }
 
@@ -58,7 +88,29 @@ impl BitbucketClient {
         Ok(parsed_body)
     }
 
+    /// Resolves the configured repositories, discovering the repositories of configured projects.
+    async fn get_repositories(&self) -> anyhow::Result<Vec<Repository>> {
+        let mut repositories = Vec::new();
+        for selector in &self.repositories {
+            match selector {
+                RepositorySelector::Repository(repository) => {
+                    merge_repositories(&mut repositories, [repository.clone()])
+                }
+                // Bitbucket projects have no subgroups
+                RepositorySelector::Namespace { namespace, .. } => {
+                    let discovered =
+                        discover_project_repositories(self, namespace, &self.discovery).await?;
+                    merge_repositories(&mut repositories, discovered);
+                }
+            }
+        }
+        Ok(repositories)
+    }
+
     pub async fn load_repositories_data(&self) -> anyhow::Result<RepositoriesData> {
-        load_repositories_data(&self.url, self, &self.repositories).await
+        let repositories = self.get_repositories().await?;
+        let data = load_repositories_data(self, &repositories).await?;
+        self.ahead_behind_cache.lock().unwrap().finish_refresh();
+        Ok(data)
     }
 }
@@ -1,37 +1,106 @@
+use serde::de::IgnoredAny;
 use serde_derive::Deserialize;
 
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct PaginatedResponse<T> {
     pub values: Vec<T>,
+    #[serde(default = "default_is_last_page")]
+    pub is_last_page: bool,
+    pub next_page_start: Option<u32>,
+}
+
+fn default_is_last_page() -> bool {
+    true
 }
 
 #[derive(Deserialize)]
 pub struct RepositoryResponse {
-    pub name: String,
     pub links: LinksResponse,
 }
 
+#[derive(Deserialize)]
+pub struct ProjectRepositoryResponse {
+    pub slug: String,
+    // only present on Bitbucket 8.0 and later
+    #[serde(default)]
+    pub archived: bool,
+    // only present for forks
+    pub origin: Option<IgnoredAny>,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BranchResponse {
     pub display_id: String,
     pub latest_commit: String,
+    // only present when requested with details
+    #[serde(default)]
+    pub metadata: BranchMetadataResponse,
+}
+
+#[derive(Deserialize, Default)]
+pub struct BranchMetadataResponse {
+    #[serde(rename = "com.atlassian.bitbucket.server.bitbucket-branch:latest-commit-metadata")]
+    pub latest_commit: Option<CommitMetadataResponse>,
+    // relative to the default branch
+    #[serde(
+        rename = "com.atlassian.bitbucket.server.bitbucket-branch:ahead-behind-metadata-provider"
+    )]
+    pub ahead_behind: Option<AheadBehindMetadataResponse>,
+}
+
+#[derive(Deserialize)]
+pub struct AheadBehindMetadataResponse {
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommitMetadataResponse {
+    pub id: String,
+    pub author: CommitAuthorResponse,
+    #[serde(default)]
+    pub message: String,
+    // epoch time in millis
+    pub committer_timestamp: u64,
+}
+
+#[derive(Deserialize)]
+pub struct CommitAuthorResponse {
+    pub name: String,
 }
 
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct BuildStatusResponse {
+    pub key: String,
+    pub name: Option<String>,
     pub state: BitbucketBuildState,
     pub url: String,
+    // epoch time in millis of the last update of the state
+    pub date_added: u64,
+    // in millis, only present on Bitbucket 7.4 and later
+    pub duration: Option<u64>,
 }
 
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PullRequestResponse {
+    pub id: u32,
+    pub title: String,
+    pub description: Option<String>,
+    // only present on Bitbucket 8.18 and later
+    #[serde(default)]
+    pub draft: bool,
     pub from_ref: GitRefResponse,
     pub to_ref: GitRefResponse,
     pub author: PullRequestUserResponse,
     pub reviewers: Vec<PullRequestUserResponse>,
     // epoch time in millis
+    pub created_date: u64,
+    // epoch time in millis
     pub updated_date: u64,
     pub links: LinksResponse,
     // this property is not officially documented but presumably used by the Bitbucket UI
@@ -44,6 +113,21 @@ pub struct PullRequestPropertiesResponse {
     pub comment_count: Option<u32>,
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeStatusResponse {
+    pub can_merge: bool,
+    pub conflicted: bool,
+    #[serde(default)]
+    pub vetoes: Vec<MergeVetoResponse>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MergeVetoResponse {
+    pub summary_message: String,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GitRefResponse {
@@ -55,6 +139,24 @@ pub struct GitRefResponse {
 pub struct PullRequestUserResponse {
     pub user: PullRequestUserDetailsResponse,
     pub approved: bool,
+    pub status: ReviewerStatus,
+}
+
+#[derive(Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ReviewerStatus {
+    Approved,
+    NeedsWork,
+    Unapproved,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PullRequestSettingsResponse {
+    // configured by the minimum approvals merge check
+    pub required_approvers: Option<u32>,
+    #[serde(default)]
+    pub required_all_approvers: bool,
 }
 
 #[derive(Deserialize)]
@@ -82,7 +184,7 @@ pub struct LinkResponse {
     pub href: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, PartialEq, Eq)]
 pub enum BitbucketBuildState {
     #[serde(rename = "SUCCESSFUL")]
     Successful,
@@ -90,4 +192,9 @@ pub enum BitbucketBuildState {
     InProgress,
     #[serde(rename = "FAILED")]
     Failed,
+    #[serde(rename = "CANCELLED")]
+    Cancelled,
+    // also covers states added by future versions, which must not abort the refresh
+    #[serde(rename = "UNKNOWN", other)]
+    Unknown,
 }
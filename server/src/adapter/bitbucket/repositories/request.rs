@@ -2,22 +2,30 @@ use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 
 use anyhow::{anyhow, Context};
-use chrono::{TimeZone, Utc};
+use chrono::{DateTime, TimeZone, Utc};
+use log::warn;
+use serde::de::IgnoredAny;
 
 use crate::adapter::bitbucket::repositories::model::{
-    BitbucketBuildState, BranchResponse, BuildStatusResponse, PaginatedResponse,
-    PullRequestResponse, RepositoryResponse, UserResponse,
+    BitbucketBuildState, BranchResponse, BuildStatusResponse, MergeStatusResponse,
+    PaginatedResponse, ProjectRepositoryResponse, PullRequestResponse, PullRequestSettingsResponse,
+    RepositoryResponse, ReviewerStatus, UserResponse,
 };
 use crate::adapter::bitbucket::repositories::BitbucketClient;
+use crate::config::RepositoryDiscoveryConfiguration;
 use crate::service::repositories::model::{
-    PipelineStatus, PullRequest, PullRequestTargetBranch, RepositoriesData, Repository,
-    RepositoryBranchData, StandaloneBranch,
+    AheadBehind, Commit, MergeBlocker, MergeBlockerKind, Mergeability, PipelineJob, PipelineStatus,
+    PipelineTiming, PullRequest, PullRequestTargetBranch, RepositoriesData, Repository,
+    RepositoryBranchData, ReviewSummary, Reviewer, StandaloneBranch,
 };
 
+const DISCOVERY_PAGE_SIZE: u32 = 100;
+const COMPARE_PAGE_SIZE: u32 = 1000;
+
 fn get_repo_sub_url(repository: &Repository, suffix: &str) -> String {
     format!(
         "api/latest/projects/{}/repos/{}/{}",
-        repository.group, repository.name, suffix
+        repository.namespace, repository.name, suffix
     )
 }
 
@@ -29,54 +37,84 @@ fn get_user_url(user_slug: &str) -> String {
     format!("api/latest/users/{user_slug}?avatarSize=32")
 }
 
+/// Responses loaded while refreshing, shared between repositories to avoid loading them twice.
+#[derive(Default)]
+struct ResponseCache {
+    users: HashMap<String, UserResponse>,
+    // every build reported for a commit, newest first
+    build_statuses: HashMap<String, Vec<BuildStatusResponse>>,
+    // keyed by PR ID, only holds the PRs of the current repository, missing if not loadable
+    merge_statuses: HashMap<u32, MergeStatusResponse>,
+}
+
 pub async fn load_repositories_data(
-    bitbucket_url: &str,
     client: &BitbucketClient,
     repositories: &[Repository],
 ) -> anyhow::Result<RepositoriesData> {
-    let mut user_map: HashMap<String, UserResponse> = HashMap::new();
-    // Option<BuildStatusResponse> because not every commit has a corresponding build
-    let mut build_status_map: HashMap<String, Option<BuildStatusResponse>> = HashMap::new();
+    let mut cache = ResponseCache::default();
 
     let mut repository_branch_datas: Vec<RepositoryBranchData> = Vec::new();
 
     for repository in repositories {
         let repository_response = get_repository(client, repository).await?;
-        let branches = get_branches(client, repository).await?;
+        let pull_requests = get_pull_requests(client, repository).await?;
+        let pull_request_settings = get_pull_request_settings(client, repository).await;
+        let mut branches = get_branches(client, repository).await?;
+        // hidden branches are skipped before loading their build statuses
+        let filter = client.branch_filters.get_filter(&repository.to_string());
+        branches.retain(|branch| {
+            is_pull_request_branch(&pull_requests, &branch.display_id)
+                || filter.is_shown(&branch.display_id, get_last_commit_date(branch))
+        });
+        // PR IDs are only unique within the repository
+        cache.merge_statuses.clear();
 
         for branch in &branches {
             let commit_id = branch.latest_commit.clone();
-            if let Entry::Vacant(e) = build_status_map.entry(commit_id) {
-                let build_status = get_build_status(client, e.key()).await?;
-                e.insert(build_status);
+            if let Entry::Vacant(e) = cache.build_statuses.entry(commit_id) {
+                let build_statuses = get_build_statuses(client, e.key()).await?;
+                e.insert(build_statuses);
             }
         }
 
-        let pull_requests = get_pull_requests(client, repository).await?;
-
         for pull_request in &pull_requests {
             let commit_id = pull_request.from_ref.latest_commit.clone();
 
-            if let Entry::Vacant(e) = build_status_map.entry(commit_id) {
-                let build_status = get_build_status(client, e.key()).await?;
-                e.insert(build_status);
+            if let Entry::Vacant(e) = cache.build_statuses.entry(commit_id) {
+                let build_statuses = get_build_statuses(client, e.key()).await?;
+                e.insert(build_statuses);
+            }
+
+            // fills the ahead/behind cache of the client, which is read when mapping
+            get_ahead_behind(
+                client,
+                repository,
+                &pull_request.from_ref.latest_commit,
+                &pull_request.to_ref.latest_commit,
+            )
+            .await;
+
+            if let Some(merge_status) = get_merge_status(client, repository, pull_request.id).await
+            {
+                cache.merge_statuses.insert(pull_request.id, merge_status);
             }
 
             let user_slug = pull_request.author.user.slug.clone();
 
-            if let Entry::Vacant(e) = user_map.entry(user_slug) {
+            if let Entry::Vacant(e) = cache.users.entry(user_slug) {
                 let user = get_user(client, e.key()).await?;
                 e.insert(user);
             }
         }
 
         let repository_branch_data = map_repository_data(
-            bitbucket_url,
+            client,
+            repository,
             repository_response,
             branches,
             pull_requests,
-            &user_map,
-            &build_status_map,
+            &cache,
+            &pull_request_settings,
         )?;
         repository_branch_datas.push(repository_branch_data);
     }
@@ -86,9 +124,44 @@ pub async fn load_repositories_data(
         last_updated_date: Some(last_updated_date),
         repositories: repository_branch_datas,
         currently_refreshing: false,
+        stale: false,
     })
 }
 
+/// Lists the repositories of a project, following the pagination of the API.
+pub async fn discover_project_repositories(
+    client: &BitbucketClient,
+    project_key: &str,
+    discovery: &RepositoryDiscoveryConfiguration,
+) -> anyhow::Result<Vec<Repository>> {
+    let mut repositories: Vec<ProjectRepositoryResponse> = Vec::new();
+    let mut start = 0;
+    loop {
+        let url = format!(
+            "api/latest/projects/{project_key}/repos?limit={DISCOVERY_PAGE_SIZE}&start={start}"
+        );
+        let response: PaginatedResponse<ProjectRepositoryResponse> = client
+            .request(&url)
+            .await
+            .with_context(|| format!("Could not load repositories of project {project_key}."))?;
+        repositories.extend(response.values);
+        match response.next_page_start {
+            Some(next_page_start) if !response.is_last_page => start = next_page_start,
+            _ => break,
+        }
+    }
+
+    Ok(repositories
+        .into_iter()
+        .filter(|repository| !(discovery.exclude_archived && repository.archived))
+        .filter(|repository| !(discovery.exclude_forks && repository.origin.is_some()))
+        .map(|repository| Repository {
+            name: repository.slug,
+            namespace: project_key.to_string(),
+        })
+        .collect())
+}
+
 async fn get_repository(
     client: &BitbucketClient,
     repository: &Repository,
@@ -104,7 +177,8 @@ async fn get_branches(
     client: &BitbucketClient,
     repository: &Repository,
 ) -> anyhow::Result<Vec<BranchResponse>> {
-    let url = get_repo_sub_url(repository, "branches");
+    // the details contain the latest commit
+    let url = get_repo_sub_url(repository, "branches?details=true");
     let response: PaginatedResponse<BranchResponse> = client
         .request(&url)
         .await
@@ -126,6 +200,115 @@ async fn get_pull_requests(
     Ok(pull_request_response.values)
 }
 
+/// Loads the approvals required by the merge checks. Falls back to no requirements if the settings
+/// cannot be read, since they are not essential for the dashboard.
+async fn get_pull_request_settings(
+    client: &BitbucketClient,
+    repository: &Repository,
+) -> PullRequestSettingsResponse {
+    let url = get_repo_sub_url(repository, "settings/pull-requests");
+    client.request(&url).await.unwrap_or_else(|err| {
+        warn!(
+            "Could not load pull request settings for repository {}: {:#}",
+            repository, err
+        );
+        PullRequestSettingsResponse::default()
+    })
+}
+
+/// Counts the commits of the source which are not in the target and vice versa. The counts are
+/// only informative, so they are left out if the commits cannot be compared.
+async fn get_ahead_behind(
+    client: &BitbucketClient,
+    repository: &Repository,
+    source_commit: &str,
+    target_commit: &str,
+) -> Option<AheadBehind> {
+    let key = (source_commit.to_string(), target_commit.to_string());
+    if let Some(ahead_behind) = client.ahead_behind_cache.lock().unwrap().get(&key) {
+        return Some(ahead_behind);
+    }
+
+    match count_ahead_behind(client, repository, source_commit, target_commit).await {
+        Ok(ahead_behind) => {
+            client
+                .ahead_behind_cache
+                .lock()
+                .unwrap()
+                .insert(key, ahead_behind);
+            Some(ahead_behind)
+        }
+        Err(err) => {
+            warn!(
+                "Could not compare commits {} and {} of repository {}: {:#}",
+                source_commit, target_commit, repository, err
+            );
+            None
+        }
+    }
+}
+
+async fn count_ahead_behind(
+    client: &BitbucketClient,
+    repository: &Repository,
+    source_commit: &str,
+    target_commit: &str,
+) -> anyhow::Result<AheadBehind> {
+    Ok(AheadBehind {
+        ahead: count_commits_not_in(client, repository, source_commit, target_commit).await?,
+        behind: count_commits_not_in(client, repository, target_commit, source_commit).await?,
+    })
+}
+
+/// Counts the commits reachable from `from` but not from `to`, following the pagination of the API.
+async fn count_commits_not_in(
+    client: &BitbucketClient,
+    repository: &Repository,
+    from: &str,
+    to: &str,
+) -> anyhow::Result<u32> {
+    let mut count = 0;
+    let mut start = 0;
+    loop {
+        let url = get_repo_sub_url(
+            repository,
+            &format!("compare/commits?from={from}&to={to}&limit={COMPARE_PAGE_SIZE}&start={start}"),
+        );
+        let response: PaginatedResponse<IgnoredAny> = client
+            .request(&url)
+            .await
+            .with_context(|| format!("Could not compare commits {from} and {to}."))?;
+        count += response.values.len() as u32;
+        match response.next_page_start {
+            Some(next_page_start) if !response.is_last_page => start = next_page_start,
+            _ => return Ok(count),
+        }
+    }
+}
+
+/// Checks whether a pull request can be merged, including the vetoes of the merge checks. The
+/// mergeability is left out if it cannot be loaded, since it is not essential for the dashboard.
+async fn get_merge_status(
+    client: &BitbucketClient,
+    repository: &Repository,
+    pull_request_id: u32,
+) -> Option<MergeStatusResponse> {
+    let url = get_repo_sub_url(
+        repository,
+        &format!("pull-requests/{pull_request_id}/merge"),
+    );
+    match client.request(&url).await {
+        Ok(response) => Some(response),
+        Err(err) => {
+            warn!(
+                "Could not load merge status for repository {} and PR {}: {:#}",
+                repository, pull_request_id, err
+            );
+            None
+        }
+    }
+}
+
 async fn get_user(client: &BitbucketClient, user_slug: &str) -> anyhow::Result<UserResponse> {
     let url = get_user_url(user_slug);
     let response = client
@@ -135,29 +318,28 @@ async fn get_user(client: &BitbucketClient, user_slug: &str) -> anyhow::Result<U
     Ok(response)
 }
 
-async fn get_build_status(
+async fn get_build_statuses(
     client: &BitbucketClient,
     commit_id: &str,
-) -> anyhow::Result<Option<BuildStatusResponse>> {
+) -> anyhow::Result<Vec<BuildStatusResponse>> {
     let url = get_build_status_url(commit_id);
     let response: PaginatedResponse<BuildStatusResponse> = client
         .request(&url)
         .await
         .with_context(|| format!("Could not load build status for commit {commit_id}."))?;
-    // get first element
-    let build_status = response.values.into_iter().take(1).last();
-    Ok(build_status)
+    Ok(response.values)
 }
 
 fn map_repository_data(
-    bitbucket_url: &str,
-    mut repository: RepositoryResponse,
+    client: &BitbucketClient,
+    repository: &Repository,
+    mut repository_response: RepositoryResponse,
     branches: Vec<BranchResponse>,
     pull_requests: Vec<PullRequestResponse>,
-    users: &HashMap<String, UserResponse>,
-    build_statuses: &HashMap<String, Option<BuildStatusResponse>>,
+    cache: &ResponseCache,
+    pull_request_settings: &PullRequestSettingsResponse,
 ) -> anyhow::Result<RepositoryBranchData> {
-    let repository_url = repository
+    let repository_url = repository_response
         .links
         .self_link
         .pop()
@@ -177,25 +359,38 @@ fn map_repository_data(
                     .filter(|pr| pr.to_ref.display_id.eq(*name))
                     .map(|pr| {
                         let source_commit = &pr.from_ref.latest_commit;
-                        let build_status = build_statuses.get(source_commit).ok_or_else(|| {
-                            anyhow!(
-                                "Did not find cached build status for commit {} and branch {}.",
-                                source_commit,
-                                pr.from_ref.display_id
-                            )
-                        })?;
+                        let build_statuses =
+                            cache.build_statuses.get(source_commit).ok_or_else(|| {
+                                anyhow!(
+                                    "Did not find cached build status for commit {} and branch {}.",
+                                    source_commit,
+                                    pr.from_ref.display_id
+                                )
+                            })?;
                         let author_slug = &pr.author.user.slug;
-                        let author = users.get(author_slug).ok_or_else(|| {
+                        let author = cache.users.get(author_slug).ok_or_else(|| {
                             anyhow!("Did not find cached user for slug {}.", author_slug)
                         })?;
-                        let approved = pr.reviewers.iter().any(|reviewer| reviewer.approved);
-
-                        let last_updated_date = Utc
-                            .timestamp_millis_opt(pr.updated_date as i64)
-                            .single()
-                            .ok_or_else(|| anyhow!("Could not parse milliseconds PR timestamp."))?;
-                        let formatted_last_updated_date =
-                            last_updated_date.format("%+").to_string();
+                        let reviewers: Vec<Reviewer> = pr
+                            .reviewers
+                            .iter()
+                            .map(|reviewer| Reviewer {
+                                user_id: reviewer.user.slug.to_owned(),
+                                user_name: reviewer.user.display_name.to_owned(),
+                                approved: reviewer.approved,
+                                changes_requested: reviewer.status == ReviewerStatus::NeedsWork,
+                            })
+                            .collect();
+                        let approvals_required = if pull_request_settings.required_all_approvers {
+                            Some(reviewers.len() as u32)
+                        } else {
+                            pull_request_settings
+                                .required_approvers
+                                .filter(|required| *required > 0)
+                        };
+
+                        let formatted_created_date = format_millis_timestamp(pr.created_date)?;
+                        let formatted_last_updated_date = format_millis_timestamp(pr.updated_date)?;
                         let link_response =
                             pr.links.self_link.first().ok_or_else(|| {
                                 anyhow!("Did not find self link for pull request.")
@@ -203,17 +398,31 @@ fn map_repository_data(
 
                         let mut avatar_url = author.avatar_url.to_owned();
                         if avatar_url.starts_with('/') {
-                            avatar_url = format!("{bitbucket_url}{avatar_url}");
+                            avatar_url = format!("{}{}", client.url, avatar_url);
                         }
 
                         Ok(PullRequest {
+                            number: pr.id,
+                            title: pr.title.to_owned(),
+                            description: pr.description.to_owned(),
+                            draft: pr.draft,
                             branch_name: pr.from_ref.display_id.to_owned(),
                             user_name: pr.author.user.display_name.to_owned(),
-                            pipeline_status: map_pipeline_status(build_status),
-                            pipeline_url: build_status.as_ref().map(|status| status.url.to_owned()),
+                            user_id: pr.author.user.slug.to_owned(),
+                            pipeline_status: map_pipeline_status(build_statuses),
+                            pipeline_url: map_pipeline_url(build_statuses),
+                            pipeline_jobs: map_pipeline_jobs(build_statuses),
+                            pipeline_timing: map_pipeline_timing(source_commit, build_statuses)?,
                             comment_count: pr.properties.comment_count.unwrap_or(0),
-                            approved,
+                            review: ReviewSummary::new(&reviewers, approvals_required),
+                            ahead_behind: client.ahead_behind_cache.lock().unwrap().get(&(
+                                pr.from_ref.latest_commit.clone(),
+                                pr.to_ref.latest_commit.clone(),
+                            )),
+                            mergeability: cache.merge_statuses.get(&pr.id).map(map_mergeability),
+                            reviewers,
                             user_profile_image: avatar_url,
+                            created_date: formatted_created_date,
                             last_activity_date: formatted_last_updated_date,
                             link_url: link_response.href.to_owned(),
                         })
@@ -229,21 +438,27 @@ fn map_repository_data(
                     })?;
 
                 let target_branch_commit = &target_branch_response.latest_commit;
-                let target_branch_build_status =
-                    build_statuses.get(target_branch_commit).ok_or_else(|| {
-                        anyhow!(
-                            "Did not find build status for commit {} and branch {}.",
-                            target_branch_commit,
-                            target_branch_response.display_id
-                        )
-                    })?;
+                let target_branch_build_statuses = cache
+                    .build_statuses
+                    .get(target_branch_commit)
+                    .ok_or_else(|| {
+                    anyhow!(
+                        "Did not find build status for commit {} and branch {}.",
+                        target_branch_commit,
+                        target_branch_response.display_id
+                    )
+                })?;
 
                 Ok(PullRequestTargetBranch {
                     branch_name: name.to_string(),
-                    pipeline_status: map_pipeline_status(target_branch_build_status),
-                    pipeline_url: target_branch_build_status
-                        .as_ref()
-                        .map(|status| status.url.to_owned()),
+                    pipeline_status: map_pipeline_status(target_branch_build_statuses),
+                    pipeline_url: map_pipeline_url(target_branch_build_statuses),
+                    pipeline_jobs: map_pipeline_jobs(target_branch_build_statuses),
+                    pipeline_timing: map_pipeline_timing(
+                        target_branch_commit,
+                        target_branch_build_statuses,
+                    )?,
+                    last_commit: map_last_commit(target_branch_response)?,
                     pull_requests: mapped_pull_requests,
                 })
             })
@@ -252,15 +467,10 @@ fn map_repository_data(
 
     let standalone_branches = branches
         .iter()
-        .filter(|branch| {
-            !pull_requests.iter().any(|pr| {
-                pr.from_ref.display_id.eq(&branch.display_id)
-                    || pr.to_ref.display_id.eq(&branch.display_id)
-            })
-        })
+        .filter(|branch| !is_pull_request_branch(&pull_requests, &branch.display_id))
         .map(|branch| {
             let commit = &branch.latest_commit;
-            let build_status = build_statuses.get(commit).ok_or_else(|| {
+            let build_statuses = cache.build_statuses.get(commit).ok_or_else(|| {
                 anyhow!(
                     "Did not find cached build status for commit {} and branch {}.",
                     commit,
@@ -270,28 +480,358 @@ fn map_repository_data(
 
             Ok(StandaloneBranch {
                 branch_name: branch.display_id.to_owned(),
-                pipeline_status: map_pipeline_status(build_status),
-                pipeline_url: build_status.as_ref().map(|status| status.url.to_owned()),
+                pipeline_status: map_pipeline_status(build_statuses),
+                pipeline_url: map_pipeline_url(build_statuses),
+                pipeline_jobs: map_pipeline_jobs(build_statuses),
+                pipeline_timing: map_pipeline_timing(commit, build_statuses)?,
+                last_commit: map_last_commit(branch)?,
+                ahead_behind: branch.metadata.ahead_behind.as_ref().map(|ahead_behind| {
+                    AheadBehind {
+                        ahead: ahead_behind.ahead,
+                        behind: ahead_behind.behind,
+                    }
+                }),
             })
         })
         .collect::<anyhow::Result<Vec<StandaloneBranch>>>()
         .context("Could not map standalone branches.")?;
 
     Ok(RepositoryBranchData {
-        repository_name: repository.name,
+        // the project key lets namespace selections match, like the group path on Gitlab
+        repository_name: repository.to_string(),
         repository_url: repository_url.href,
         standalone_branches,
         pull_request_target_branches,
     })
 }
 
-fn map_pipeline_status(response: &Option<BuildStatusResponse>) -> PipelineStatus {
-    match response {
-        Some(response) => match response.state {
-            BitbucketBuildState::Successful => PipelineStatus::Successful,
-            BitbucketBuildState::InProgress => PipelineStatus::Running,
-            BitbucketBuildState::Failed => PipelineStatus::Failed,
-        },
-        None => PipelineStatus::None,
+fn is_pull_request_branch(pull_requests: &[PullRequestResponse], branch_name: &str) -> bool {
+    pull_requests
+        .iter()
+        .any(|pr| pr.from_ref.display_id.eq(branch_name) || pr.to_ref.display_id.eq(branch_name))
+}
+
+fn get_last_commit_date(branch: &BranchResponse) -> Option<DateTime<Utc>> {
+    let commit_metadata = branch.metadata.latest_commit.as_ref()?;
+    Utc.timestamp_millis_opt(commit_metadata.committer_timestamp as i64)
+        .single()
+}
+
+fn map_last_commit(branch: &BranchResponse) -> anyhow::Result<Option<Commit>> {
+    let Some(commit_metadata) = &branch.metadata.latest_commit else {
+        return Ok(None);
+    };
+    Ok(Some(Commit {
+        sha: commit_metadata.id.to_owned(),
+        author: commit_metadata.author.name.to_owned(),
+        message_headline: Commit::get_message_headline(&commit_metadata.message),
+        date: format_millis_timestamp(commit_metadata.committer_timestamp)?,
+    }))
+}
+
+/// Vetoes do not tell which merge check raised them, so only conflicts get a specific kind.
+fn map_mergeability(response: &MergeStatusResponse) -> Mergeability {
+    let mut blockers = Vec::new();
+    if response.conflicted {
+        blockers.push(MergeBlocker {
+            kind: MergeBlockerKind::Conflicts,
+            message: "The pull request has merge conflicts.".to_string(),
+        });
+    }
+    blockers.extend(response.vetoes.iter().map(|veto| MergeBlocker {
+        kind: MergeBlockerKind::Other,
+        message: veto.summary_message.to_owned(),
+    }));
+    Mergeability {
+        can_merge: response.can_merge,
+        has_conflicts: response.conflicted,
+        blockers,
+    }
+}
+
+/// Builds only report when they last changed their state and how long they took, so the pipeline
+/// is taken to start with the earliest build and to finish with the last one.
+fn map_pipeline_timing(
+    commit_id: &str,
+    responses: &[BuildStatusResponse],
+) -> anyhow::Result<Option<PipelineTiming>> {
+    if responses.is_empty() {
+        return Ok(None);
+    }
+    let started_millis = responses
+        .iter()
+        .filter_map(|response| {
+            let duration = response.duration?;
+            Some(response.date_added.saturating_sub(duration))
+        })
+        .min();
+    let finished_millis = responses
+        .iter()
+        .all(|response| response.state != BitbucketBuildState::InProgress)
+        .then(|| responses.iter().map(|response| response.date_added).max())
+        .flatten();
+    let duration_seconds = match (started_millis, finished_millis) {
+        (Some(started), Some(finished)) => Some(finished.saturating_sub(started) / 1000),
+        _ => None,
+    };
+
+    Ok(Some(PipelineTiming {
+        commit_sha: commit_id.to_string(),
+        created_date: None,
+        started_date: started_millis.map(format_millis_timestamp).transpose()?,
+        finished_date: finished_millis.map(format_millis_timestamp).transpose()?,
+        duration_seconds,
+    }))
+}
+
+fn format_millis_timestamp(millis: u64) -> anyhow::Result<String> {
+    let timestamp = Utc
+        .timestamp_millis_opt(millis as i64)
+        .single()
+        .ok_or_else(|| anyhow!("Could not parse milliseconds PR timestamp."))?;
+    Ok(timestamp.format("%+").to_string())
+}
+
+/// Combines the builds reported for a commit, e.g. separate lint and test builds.
+fn map_pipeline_status(responses: &[BuildStatusResponse]) -> PipelineStatus {
+    PipelineStatus::combine(
+        responses
+            .iter()
+            .map(|response| map_build_state(&response.state)),
+    )
+}
+
+/// Links the build which determines the combined status.
+fn map_pipeline_url(responses: &[BuildStatusResponse]) -> Option<String> {
+    let status = map_pipeline_status(responses);
+    responses
+        .iter()
+        .find(|response| map_build_state(&response.state) == status)
+        .map(|response| response.url.to_owned())
+}
+
+fn map_build_state(state: &BitbucketBuildState) -> PipelineStatus {
+    match state {
+        BitbucketBuildState::Successful => PipelineStatus::Successful,
+        BitbucketBuildState::InProgress => PipelineStatus::Running,
+        BitbucketBuildState::Failed => PipelineStatus::Failed,
+        BitbucketBuildState::Cancelled => PipelineStatus::Canceled,
+        BitbucketBuildState::Unknown => PipelineStatus::None,
+    }
+}
+
+/// Each build reported for the commit is treated as a job of the pipeline. They are loaded anyway,
+/// so they are kept regardless of the status.
+fn map_pipeline_jobs(responses: &[BuildStatusResponse]) -> Vec<PipelineJob> {
+    responses
+        .iter()
+        .map(|response| PipelineJob {
+            name: response
+                .name
+                .clone()
+                .unwrap_or_else(|| response.key.clone()),
+            stage: None,
+            status: map_build_state(&response.state),
+            duration_seconds: response.duration.map(|millis| millis / 1000),
+            url: Some(response.url.to_owned()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use axum::extract::Query;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use axum::{Json, Router};
+    use serde_json::json;
+
+    use crate::adapter::bitbucket::repositories::model::{BuildStatusResponse, PaginatedResponse};
+    use crate::adapter::bitbucket::repositories::request::{
+        discover_project_repositories, get_ahead_behind, get_merge_status, map_pipeline_jobs,
+        map_pipeline_status, map_pipeline_url,
+    };
+    use crate::adapter::bitbucket::repositories::BitbucketClient;
+    use crate::config::{BranchFiltersConfiguration, RepositoryDiscoveryConfiguration};
+    use crate::service::repositories::branch_filter::BranchFilters;
+    use crate::service::repositories::model::{AheadBehind, PipelineStatus, Repository};
+    use crate::test_support::serve_locally;
+
+    fn get_client(url: String) -> BitbucketClient {
+        BitbucketClient::new(
+            &[],
+            url,
+            "user".to_string(),
+            "password".to_string(),
+            RepositoryDiscoveryConfiguration::default(),
+            BranchFilters::new(&BranchFiltersConfiguration::default()).unwrap(),
+        )
+        .unwrap()
+    }
+
+    // recorded from Bitbucket 8.9, with the state of the last build changed to one not known yet
+    const BUILD_STATUSES: &str = r#"{
+        "size": 4,
+        "limit": 25,
+        "isLastPage": true,
+        "values": [
+            {
+                "state": "CANCELLED",
+                "key": "LINT",
+                "name": "Lint",
+                "url": "https://ci.example.com/builds/12",
+                "description": "Cancelled by Jane",
+                "dateAdded": 1706781600000,
+                "duration": 12000
+            },
+            {
+                "state": "FAILED",
+                "key": "TEST",
+                "url": "https://ci.example.com/builds/11",
+                "dateAdded": 1706781500000
+            },
+            {
+                "state": "UNKNOWN",
+                "key": "DEPLOY",
+                "name": "Deploy",
+                "url": "https://ci.example.com/builds/10",
+                "dateAdded": 1706781400000
+            },
+            {
+                "state": "NOT_BUILT",
+                "key": "DOCS",
+                "name": "Docs",
+                "url": "https://ci.example.com/builds/9",
+                "dateAdded": 1706781300000
+            }
+        ],
+        "start": 0
+    }"#;
+
+    fn get_build_statuses(keys: &[&str]) -> Vec<BuildStatusResponse> {
+        let response: PaginatedResponse<BuildStatusResponse> =
+            serde_json::from_str(BUILD_STATUSES).unwrap();
+        response
+            .values
+            .into_iter()
+            .filter(|build_status| keys.contains(&build_status.key.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn map_every_build_state() {
+        let build_statuses = get_build_statuses(&["LINT", "TEST", "DEPLOY", "DOCS"]);
+        let job_statuses: Vec<PipelineStatus> = map_pipeline_jobs(&build_statuses)
+            .into_iter()
+            .map(|job| job.status)
+            .collect();
+
+        assert_eq!(
+            vec![
+                PipelineStatus::Canceled,
+                PipelineStatus::Failed,
+                PipelineStatus::None,
+                PipelineStatus::None
+            ],
+            job_statuses
+        );
+        assert_eq!(PipelineStatus::Failed, map_pipeline_status(&build_statuses));
+        assert_eq!(
+            Some("https://ci.example.com/builds/11".to_string()),
+            map_pipeline_url(&build_statuses)
+        );
+        assert_eq!(
+            PipelineStatus::Canceled,
+            map_pipeline_status(&get_build_statuses(&["LINT", "DEPLOY"]))
+        );
+    }
+
+    #[tokio::test]
+    async fn discover_project_repositories_follows_pages() {
+        let url = serve_locally(|_| {
+            Router::new().route(
+                "/rest/api/latest/projects/PROJECT/repos",
+                get(|Query(query): Query<HashMap<String, String>>| async move {
+                    if query.get("start").map(String::as_str) == Some("2") {
+                        Json(json!({"values": [{"slug": "repo3"}], "isLastPage": true}))
+                    } else {
+                        Json(json!({
+                            "values": [{"slug": "repo1"}, {"slug": "repo2", "archived": true}],
+                            "isLastPage": false,
+                            "nextPageStart": 2
+                        }))
+                    }
+                }),
+            )
+        })
+        .await;
+        let client = get_client(url);
+
+        let repositories = discover_project_repositories(
+            &client,
+            "PROJECT",
+            &RepositoryDiscoveryConfiguration::default(),
+        )
+        .await
+        .unwrap();
+
+        let slugs: Vec<String> = repositories.iter().map(ToString::to_string).collect();
+        assert_eq!(vec!["PROJECT/repo1", "PROJECT/repo3"], slugs);
+    }
+
+    #[tokio::test]
+    async fn get_ahead_behind_counts_every_page() {
+        let url = serve_locally(|_| {
+            Router::new().route(
+                "/rest/api/latest/projects/PROJECT/repos/repo/compare/commits",
+                get(|Query(query): Query<HashMap<String, String>>| async move {
+                    let start = query.get("start").map(String::as_str);
+                    let page = match (query["from"].as_str(), start) {
+                        ("feature1", Some("0")) => {
+                            json!({"values": [{}, {}], "isLastPage": false, "nextPageStart": 2})
+                        }
+                        ("feature1", Some("2")) => json!({"values": [{}], "isLastPage": true}),
+                        ("main1", _) => json!({"values": [{}], "isLastPage": true}),
+                        _ => return Err(StatusCode::NOT_FOUND),
+                    };
+                    Ok(Json(page))
+                }),
+            )
+        })
+        .await;
+        let client = get_client(url);
+        let repository = Repository::from_slug("PROJECT/repo").unwrap();
+
+        assert_eq!(
+            Some(AheadBehind {
+                ahead: 3,
+                behind: 1
+            }),
+            get_ahead_behind(&client, &repository, "feature1", "main1").await
+        );
+        assert_eq!(
+            None,
+            get_ahead_behind(&client, &repository, "unknown", "main1").await
+        );
+    }
+
+    #[tokio::test]
+    async fn get_merge_status_falls_back_to_none() {
+        let url = serve_locally(|_| {
+            Router::new().route(
+                "/rest/api/latest/projects/PROJECT/repos/repo/pull-requests/1/merge",
+                get(|| async {
+                    Json(json!({"canMerge": true, "conflicted": false, "vetoes": []}))
+                }),
+            )
+        })
+        .await;
+        let client = get_client(url);
+        let repository = Repository::from_slug("PROJECT/repo").unwrap();
+
+        assert!(get_merge_status(&client, &repository, 1).await.is_some());
+        assert!(get_merge_status(&client, &repository, 2).await.is_none());
     }
 }
@@ -0,0 +1,98 @@
+use anyhow::{anyhow, Context};
+use serde::Serialize;
+
+use crate::adapter::chat::model::{
+    MattermostPayload, SlackPayload, TeamsOpenUriAction, TeamsOpenUriTarget, TeamsPayload,
+};
+use crate::config::ChatKind;
+use crate::service::notifications::model::ChatMessage;
+
+mod model;
+
+/// Posts messages to incoming webhooks of chat tools.
+#[derive(Clone)]
+pub struct ChatWebhookClient {
+    client: reqwest::Client,
+}
+
+impl ChatWebhookClient {
+    pub fn new() -> Self {
+        ChatWebhookClient {
+            client: reqwest::Client::new(),
+        }
+    }
+
+    pub async fn post_message(
+        &self,
+        kind: ChatKind,
+        url: &str,
+        message: &ChatMessage,
+    ) -> anyhow::Result<()> {
+        match kind {
+            ChatKind::Slack => self.post(url, &map_slack_payload(message)).await,
+            ChatKind::Mattermost => self.post(url, &map_mattermost_payload(message)).await,
+            ChatKind::Teams => self.post(url, &map_teams_payload(message)).await,
+        }
+    }
+
+    async fn post<T: Serialize>(&self, url: &str, payload: &T) -> anyhow::Result<()> {
+        let response = self
+            .client
+            .post(url)
+            .json(payload)
+            .send()
+            .await
+            .context("Could not make request to chat webhook.")?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Unsuccessful response from chat webhook {}: {}",
+                url,
+                response.status()
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn map_slack_payload(message: &ChatMessage) -> SlackPayload {
+    let title = match &message.link {
+        Some(link) => format!("*<{}|{}>*", link, message.title),
+        None => format!("*{}*", message.title),
+    };
+    SlackPayload {
+        text: format!("{}\n{}", title, message.text),
+    }
+}
+
+fn map_mattermost_payload(message: &ChatMessage) -> MattermostPayload {
+    let title = match &message.link {
+        Some(link) => format!("**[{}]({})**", message.title, link),
+        None => format!("**{}**", message.title),
+    };
+    MattermostPayload {
+        text: format!("{}\n{}", title, message.text),
+    }
+}
+
+fn map_teams_payload(message: &ChatMessage) -> TeamsPayload {
+    let potential_action = message
+        .link
+        .iter()
+        .map(|link| TeamsOpenUriAction {
+            action_type: "OpenUri".to_string(),
+            name: "Open".to_string(),
+            targets: vec![TeamsOpenUriTarget {
+                os: "default".to_string(),
+                uri: link.clone(),
+            }],
+        })
+        .collect();
+    TeamsPayload {
+        message_type: "MessageCard".to_string(),
+        context: "https://schema.org/extensions".to_string(),
+        summary: message.title.clone(),
+        title: message.title.clone(),
+        text: message.text.clone(),
+        potential_action,
+    }
+}
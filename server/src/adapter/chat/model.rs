@@ -0,0 +1,39 @@
+use serde_derive::Serialize;
+
+#[derive(Serialize)]
+pub struct SlackPayload {
+    pub text: String,
+}
+
+#[derive(Serialize)]
+pub struct MattermostPayload {
+    pub text: String,
+}
+
+/// Legacy actionable message card, which is what Teams incoming webhooks accept.
+#[derive(Serialize)]
+pub struct TeamsPayload {
+    #[serde(rename = "@type")]
+    pub message_type: String,
+    #[serde(rename = "@context")]
+    pub context: String,
+    pub summary: String,
+    pub title: String,
+    pub text: String,
+    #[serde(rename = "potentialAction", skip_serializing_if = "Vec::is_empty")]
+    pub potential_action: Vec<TeamsOpenUriAction>,
+}
+
+#[derive(Serialize)]
+pub struct TeamsOpenUriAction {
+    #[serde(rename = "@type")]
+    pub action_type: String,
+    pub name: String,
+    pub targets: Vec<TeamsOpenUriTarget>,
+}
+
+#[derive(Serialize)]
+pub struct TeamsOpenUriTarget {
+    pub os: String,
+    pub uri: String,
+}
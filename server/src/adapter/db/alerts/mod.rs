@@ -0,0 +1,81 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use chrono::DateTime;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, NotSet, QueryFilter, QueryOrder};
+
+use crate::service::alerts::model::{Alert, AlertRuleType, AlertTimestamp};
+
+mod alert;
+
+#[derive(Clone)]
+pub struct AlertRepository {
+    db: DatabaseConnection,
+}
+
+impl AlertRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        AlertRepository { db }
+    }
+
+    pub async fn save_alerts(&self, alerts: &[Alert]) -> anyhow::Result<()> {
+        if alerts.is_empty() {
+            return Ok(());
+        }
+        let alert_entities = alerts.iter().map(|alert| alert::ActiveModel {
+            id: NotSet,
+            rule: Set(alert.rule.to_string()),
+            repository: Set(alert.repository.clone()),
+            subject: Set(alert.subject.clone()),
+            message: Set(alert.message.clone()),
+            link: Set(alert.link.clone()),
+            raised_at: Set(alert.raised_at.to_rfc3339()),
+        });
+        alert::Entity::insert_many(alert_entities)
+            .exec(&self.db)
+            .await
+            .context("Could not insert alerts into DB.")?;
+        Ok(())
+    }
+
+    pub async fn get_alerts(&self) -> anyhow::Result<Vec<Alert>> {
+        let alerts = alert::Entity::find()
+            .order_by_asc(alert::Column::Id)
+            .all(&self.db)
+            .await
+            .context("Could not load alerts from DB.")?;
+        alerts
+            .into_iter()
+            .map(|model| {
+                let rule = AlertRuleType::from_str(&model.rule)
+                    .map_err(|_| anyhow!("Could not parse alert rule from DB."))?;
+                let raised_at = DateTime::parse_from_rfc3339(&model.raised_at)
+                    .context("Could not parse alert timestamp from DB.")?;
+                Ok(Alert {
+                    id: Some(model.id),
+                    rule,
+                    repository: model.repository,
+                    subject: model.subject,
+                    message: model.message,
+                    link: model.link,
+                    raised_at: AlertTimestamp::from(raised_at),
+                })
+            })
+            .collect::<anyhow::Result<Vec<Alert>>>()
+            .context("Could not map DB entities to service entities.")
+    }
+
+    pub async fn delete_alerts(&self, alerts: &[Alert]) -> anyhow::Result<()> {
+        let ids: Vec<i32> = alerts.iter().filter_map(|alert| alert.id).collect();
+        if ids.is_empty() {
+            return Ok(());
+        }
+        alert::Entity::delete_many()
+            .filter(alert::Column::Id.is_in(ids))
+            .exec(&self.db)
+            .await
+            .context("Could not delete alerts from DB.")?;
+        Ok(())
+    }
+}
@@ -1 +1,5 @@
+pub mod alerts;
+pub mod pipelines;
 pub mod prupdates;
+pub mod snapshots;
+pub mod webhooks;
@@ -0,0 +1,321 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use chrono::DateTime;
+use sea_orm::sea_query::{
+    Alias, Asterisk, Expr, IntoColumnRef, OnConflict, Order, OverStatement, Query, SelectStatement,
+    WindowStatement,
+};
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, NotSet, QueryFilter, QueryOrder,
+};
+
+use crate::service::pipelines::model::{PipelineRun, PipelineStatusTransition, PipelineTimestamp};
+use crate::service::repositories::model::PipelineStatus;
+
+mod pipeline_run;
+mod pipeline_status_transition;
+
+#[derive(Clone)]
+pub struct PipelineStatusTransitionRepository {
+    db: DatabaseConnection,
+}
+
+impl PipelineStatusTransitionRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        PipelineStatusTransitionRepository { db }
+    }
+
+    pub async fn save_transitions(
+        &self,
+        transitions: &[PipelineStatusTransition],
+    ) -> anyhow::Result<()> {
+        if transitions.is_empty() {
+            return Ok(());
+        }
+        let transition_entities =
+            transitions
+                .iter()
+                .map(|transition| pipeline_status_transition::ActiveModel {
+                    id: NotSet,
+                    repository: Set(transition.repository.clone()),
+                    branch: Set(transition.branch.clone()),
+                    status: Set(transition.status.to_string()),
+                    pipeline_url: Set(transition.pipeline_url.clone()),
+                    timestamp: Set(transition.timestamp.to_rfc3339()),
+                });
+        pipeline_status_transition::Entity::insert_many(transition_entities)
+            .exec(&self.db)
+            .await
+            .context("Could not insert pipeline status transitions into DB.")?;
+        Ok(())
+    }
+
+    /// Returns the most recent transition of every repository and branch.
+    pub async fn get_latest_transitions(&self) -> anyhow::Result<Vec<PipelineStatusTransition>> {
+        let latest_ids = Query::select()
+            .expr(Expr::col(pipeline_status_transition::Column::Id).max())
+            .from(pipeline_status_transition::Entity)
+            .group_by_columns([
+                pipeline_status_transition::Column::Repository,
+                pipeline_status_transition::Column::Branch,
+            ])
+            .to_owned();
+        let transitions = pipeline_status_transition::Entity::find()
+            .filter(pipeline_status_transition::Column::Id.in_subquery(latest_ids))
+            .all(&self.db)
+            .await
+            .context("Could not load latest pipeline status transitions from DB.")?;
+        map_transitions(transitions)
+    }
+
+    /// Returns the transitions of the given repository (and optionally branch) ordered from oldest
+    /// to newest.
+    pub async fn get_transitions(
+        &self,
+        repository: &str,
+        branch: Option<&str>,
+    ) -> anyhow::Result<Vec<PipelineStatusTransition>> {
+        let mut query = pipeline_status_transition::Entity::find()
+            .filter(pipeline_status_transition::Column::Repository.eq(repository));
+        if let Some(branch) = branch {
+            query = query.filter(pipeline_status_transition::Column::Branch.eq(branch));
+        }
+        let transitions = query
+            .order_by_asc(pipeline_status_transition::Column::Id)
+            .all(&self.db)
+            .await
+            .context("Could not load pipeline status transitions from DB.")?;
+        map_transitions(transitions)
+    }
+}
+
+/// Stores finished pipeline runs, one row per run.
+#[derive(Clone)]
+pub struct PipelineRunRepository {
+    db: DatabaseConnection,
+}
+
+impl PipelineRunRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        PipelineRunRepository { db }
+    }
+
+    /// Saves the runs, skipping the ones which are already stored.
+    pub async fn save_runs(&self, runs: &[PipelineRun]) -> anyhow::Result<()> {
+        if runs.is_empty() {
+            return Ok(());
+        }
+        let run_entities = runs.iter().map(|run| pipeline_run::ActiveModel {
+            id: NotSet,
+            repository: Set(run.repository.clone()),
+            branch: Set(run.branch.clone()),
+            commit_sha: Set(run.commit_sha.clone()),
+            status: Set(run.status.to_string()),
+            pipeline_url: Set(run.pipeline_url.clone()),
+            started_date: Set(run.started_date.map(|date| date.to_rfc3339())),
+            finished_date: Set(run.finished_date.to_rfc3339()),
+            duration_seconds: Set(run.duration_seconds.map(|duration| duration as i64)),
+        });
+        pipeline_run::Entity::insert_many(run_entities)
+            .on_conflict(
+                OnConflict::columns([
+                    pipeline_run::Column::Repository,
+                    pipeline_run::Column::Branch,
+                    pipeline_run::Column::CommitSha,
+                    pipeline_run::Column::FinishedDate,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec_without_returning(&self.db)
+            .await
+            .context("Could not insert pipeline runs into DB.")?;
+        Ok(())
+    }
+
+    /// Returns up to `limit` of the most recent runs with one of the given statuses for every
+    /// branch of the repository (or only the given branch), ordered from oldest to newest.
+    pub async fn get_recent_runs(
+        &self,
+        repository: &str,
+        branch: Option<&str>,
+        statuses: &[PipelineStatus],
+        limit: u64,
+    ) -> anyhow::Result<Vec<PipelineRun>> {
+        let mut ranked_runs = rank_runs_by_branch(Asterisk);
+        ranked_runs
+            .and_where(pipeline_run::Column::Repository.eq(repository))
+            .and_where(
+                pipeline_run::Column::Status
+                    .is_in(statuses.iter().map(|status| status.to_string())),
+            );
+        if let Some(branch) = branch {
+            ranked_runs.and_where(pipeline_run::Column::Branch.eq(branch));
+        }
+        let query = Query::select()
+            .column(Asterisk)
+            .from_subquery(ranked_runs, Alias::new("ranked_run"))
+            .and_where(Expr::col(Alias::new("position")).lte(limit))
+            .order_by(pipeline_run::Column::FinishedDate, Order::Asc)
+            .to_owned();
+        let runs = pipeline_run::Entity::find()
+            .from_raw_sql(self.db.get_database_backend().build(&query))
+            .all(&self.db)
+            .await
+            .context("Could not load pipeline runs from DB.")?;
+        map_runs(runs)
+    }
+
+    /// Deletes all but the given number of most recent runs of every repository and branch.
+    pub async fn prune_runs(&self, keep: u64) -> anyhow::Result<()> {
+        let pruned_ids = Query::select()
+            .column(pipeline_run::Column::Id)
+            .from_subquery(
+                rank_runs_by_branch(pipeline_run::Column::Id),
+                Alias::new("ranked_run"),
+            )
+            .and_where(Expr::col(Alias::new("position")).gt(keep))
+            .to_owned();
+        pipeline_run::Entity::delete_many()
+            .filter(pipeline_run::Column::Id.in_subquery(pruned_ids))
+            .exec(&self.db)
+            .await
+            .context("Could not delete pipeline runs from DB.")?;
+        Ok(())
+    }
+}
+
+/// Selects the given column of the runs together with their `position` among the runs of the
+/// same repository and branch, starting with 1 for the most recent one.
+fn rank_runs_by_branch(column: impl IntoColumnRef) -> SelectStatement {
+    Query::select()
+        .column(column)
+        .expr_window_as(
+            Expr::cust("ROW_NUMBER()"),
+            WindowStatement::partition_by(pipeline_run::Column::Repository)
+                .partition_by(pipeline_run::Column::Branch)
+                .order_by(pipeline_run::Column::FinishedDate, Order::Desc)
+                .to_owned(),
+            Alias::new("position"),
+        )
+        .from(pipeline_run::Entity)
+        .to_owned()
+}
+
+fn map_transitions(
+    models: Vec<pipeline_status_transition::Model>,
+) -> anyhow::Result<Vec<PipelineStatusTransition>> {
+    models
+        .into_iter()
+        .map(|model| {
+            let status = PipelineStatus::from_str(&model.status)
+                .map_err(|_| anyhow!("Could not parse pipeline status from DB."))?;
+            let timestamp = DateTime::parse_from_rfc3339(&model.timestamp)
+                .context("Could not parse transition timestamp from DB.")?;
+            Ok(PipelineStatusTransition {
+                id: Some(model.id),
+                repository: model.repository,
+                branch: model.branch,
+                status,
+                pipeline_url: model.pipeline_url,
+                timestamp: PipelineTimestamp::from(timestamp),
+            })
+        })
+        .collect::<anyhow::Result<Vec<PipelineStatusTransition>>>()
+        .context("Could not map DB entities to service entities.")
+}
+
+fn map_runs(models: Vec<pipeline_run::Model>) -> anyhow::Result<Vec<PipelineRun>> {
+    models
+        .into_iter()
+        .map(|model| {
+            let status = PipelineStatus::from_str(&model.status)
+                .map_err(|_| anyhow!("Could not parse pipeline status from DB."))?;
+            let started_date = model
+                .started_date
+                .map(|date| DateTime::parse_from_rfc3339(&date))
+                .transpose()
+                .context("Could not parse pipeline start from DB.")?;
+            let finished_date = DateTime::parse_from_rfc3339(&model.finished_date)
+                .context("Could not parse pipeline end from DB.")?;
+            Ok(PipelineRun {
+                repository: model.repository,
+                branch: model.branch,
+                commit_sha: model.commit_sha,
+                status,
+                pipeline_url: model.pipeline_url,
+                started_date: started_date.map(PipelineTimestamp::from),
+                finished_date: PipelineTimestamp::from(finished_date),
+                duration_seconds: model.duration_seconds.map(|duration| duration as u64),
+            })
+        })
+        .collect::<anyhow::Result<Vec<PipelineRun>>>()
+        .context("Could not map DB entities to service entities.")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, TimeZone, Utc};
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::{ConnectOptions, Database};
+
+    use crate::adapter::db::pipelines::PipelineRunRepository;
+    use crate::service::pipelines::model::PipelineRun;
+    use crate::service::repositories::model::PipelineStatus;
+
+    async fn get_in_memory_repository() -> PipelineRunRepository {
+        let connect_options = ConnectOptions::new("sqlite::memory:".to_owned());
+        let db_connection = Database::connect(connect_options).await.unwrap();
+        Migrator::up(&db_connection, None).await.unwrap();
+        PipelineRunRepository::new(db_connection)
+    }
+
+    fn get_run(branch: &str, minute: i64, status: PipelineStatus) -> PipelineRun {
+        PipelineRun {
+            repository: "PROJECT/repo1".to_string(),
+            branch: branch.to_string(),
+            commit_sha: format!("{branch}-{minute}"),
+            status,
+            pipeline_url: None,
+            started_date: None,
+            finished_date: Utc.with_ymd_and_hms(2024, 5, 1, 8, 0, 0).unwrap()
+                + Duration::minutes(minute),
+            duration_seconds: Some(60),
+        }
+    }
+
+    fn get_commits(runs: &[PipelineRun]) -> Vec<&str> {
+        runs.iter().map(|run| run.commit_sha.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn get_recent_runs_of_every_branch_and_prune_the_rest() {
+        let repository = get_in_memory_repository().await;
+        repository
+            .save_runs(&[
+                get_run("main", 1, PipelineStatus::Successful),
+                get_run("main", 2, PipelineStatus::Canceled),
+                get_run("main", 3, PipelineStatus::Failed),
+                get_run("main", 4, PipelineStatus::Successful),
+                get_run("feature", 5, PipelineStatus::Successful),
+            ])
+            .await
+            .unwrap();
+        let statuses = [PipelineStatus::Successful, PipelineStatus::Failed];
+
+        let runs = repository
+            .get_recent_runs("PROJECT/repo1", None, &statuses, 2)
+            .await
+            .unwrap();
+        assert_eq!(vec!["main-3", "main-4", "feature-5"], get_commits(&runs));
+
+        repository.prune_runs(2).await.unwrap();
+        let runs = repository
+            .get_recent_runs("PROJECT/repo1", Some("main"), &statuses, 10)
+            .await
+            .unwrap();
+        assert_eq!(vec!["main-3", "main-4"], get_commits(&runs));
+    }
+}
@@ -0,0 +1,21 @@
+use sea_orm::entity::prelude::*;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "pipeline_run")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: i32,
+    pub repository: String,
+    pub branch: String,
+    pub commit_sha: String,
+    pub status: String,
+    pub pipeline_url: Option<String>,
+    pub started_date: Option<String>,
+    pub finished_date: String,
+    pub duration_seconds: Option<i64>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,161 @@
+use anyhow::Context;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{DatabaseConnection, EntityTrait, QueryOrder, TransactionTrait};
+
+use crate::service::repositories::model::{RepositoriesData, RepositoryBranchData};
+
+mod repository_snapshot;
+
+/// Stores the most recently loaded data of every repository so that it can be served right away
+/// after a restart.
+#[derive(Clone)]
+pub struct RepositorySnapshotRepository {
+    db: DatabaseConnection,
+}
+
+impl RepositorySnapshotRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        RepositorySnapshotRepository { db }
+    }
+
+    pub async fn save_snapshot(&self, data: &RepositoriesData) -> anyhow::Result<()> {
+        let last_updated_date = data.last_updated_date.clone().unwrap_or_default();
+        let snapshot_entities = data
+            .repositories
+            .iter()
+            .enumerate()
+            .map(|(position, repository)| {
+                let serialized_data = serde_json::to_string(repository).with_context(|| {
+                    format!(
+                        "Could not serialize data of repository {}.",
+                        repository.repository_name
+                    )
+                })?;
+                Ok(repository_snapshot::ActiveModel {
+                    repository_name: Set(repository.repository_name.clone()),
+                    position: Set(position as i32),
+                    last_updated_date: Set(last_updated_date.clone()),
+                    data: Set(serialized_data),
+                })
+            })
+            .collect::<anyhow::Result<Vec<repository_snapshot::ActiveModel>>>()?;
+
+        let transaction = self
+            .db
+            .begin()
+            .await
+            .context("Could not start snapshot transaction.")?;
+        repository_snapshot::Entity::delete_many()
+            .exec(&transaction)
+            .await
+            .context("Could not delete previous repository snapshots from DB.")?;
+        if !snapshot_entities.is_empty() {
+            repository_snapshot::Entity::insert_many(snapshot_entities)
+                .exec(&transaction)
+                .await
+                .context("Could not insert repository snapshots into DB.")?;
+        }
+        transaction
+            .commit()
+            .await
+            .context("Could not commit snapshot transaction.")?;
+        Ok(())
+    }
+
+    /// Returns the last saved snapshot or `None` if nothing has been saved yet.
+    pub async fn load_snapshot(&self) -> anyhow::Result<Option<RepositoriesData>> {
+        let snapshots = repository_snapshot::Entity::find()
+            .order_by_asc(repository_snapshot::Column::Position)
+            .all(&self.db)
+            .await
+            .context("Could not load repository snapshots from DB.")?;
+        let Some(last_updated_date) = snapshots
+            .first()
+            .map(|snapshot| snapshot.last_updated_date.clone())
+        else {
+            return Ok(None);
+        };
+
+        let repositories = snapshots
+            .into_iter()
+            .map(|snapshot| {
+                serde_json::from_str::<RepositoryBranchData>(&snapshot.data).with_context(|| {
+                    format!(
+                        "Could not parse snapshot of repository {}.",
+                        snapshot.repository_name
+                    )
+                })
+            })
+            .collect::<anyhow::Result<Vec<RepositoryBranchData>>>()?;
+
+        Ok(Some(RepositoriesData {
+            last_updated_date: Some(last_updated_date),
+            currently_refreshing: false,
+            stale: true,
+            repositories,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::{ConnectOptions, Database};
+
+    use crate::adapter::db::snapshots::RepositorySnapshotRepository;
+    use crate::service::repositories::model::{RepositoriesData, RepositoryBranchData};
+
+    async fn get_in_memory_repository() -> RepositorySnapshotRepository {
+        let connect_options = ConnectOptions::new("sqlite::memory:".to_owned());
+        let db_connection = Database::connect(connect_options).await.unwrap();
+        Migrator::up(&db_connection, None).await.unwrap();
+        RepositorySnapshotRepository::new(db_connection)
+    }
+
+    fn get_repositories_data(repository_names: &[&str]) -> RepositoriesData {
+        RepositoriesData {
+            last_updated_date: Some("2024-05-01T10:00:00+00:00".to_string()),
+            currently_refreshing: false,
+            stale: false,
+            repositories: repository_names
+                .iter()
+                .map(|name| RepositoryBranchData {
+                    repository_name: name.to_string(),
+                    repository_url: format!("https://git.example.com/{name}"),
+                    pull_request_target_branches: vec![],
+                    standalone_branches: vec![],
+                })
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn load_snapshot_nothing_saved() {
+        let repository = get_in_memory_repository().await;
+
+        assert!(repository.load_snapshot().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn load_snapshot_replaces_previous_snapshot() {
+        let repository = get_in_memory_repository().await;
+        repository
+            .save_snapshot(&get_repositories_data(&["repo1", "repo2"]))
+            .await
+            .unwrap();
+        repository
+            .save_snapshot(&get_repositories_data(&["repo3", "repo1"]))
+            .await
+            .unwrap();
+
+        let snapshot = repository.load_snapshot().await.unwrap().unwrap();
+
+        assert!(snapshot.stale);
+        let names: Vec<String> = snapshot
+            .repositories
+            .into_iter()
+            .map(|repository| repository.repository_name)
+            .collect();
+        assert_eq!(names, vec!["repo3".to_string(), "repo1".to_string()]);
+    }
+}
@@ -0,0 +1,181 @@
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use chrono::DateTime;
+use sea_orm::ActiveValue::Set;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, NotSet, QueryFilter,
+    QueryOrder, QuerySelect,
+};
+
+use crate::service::notifications::model::{
+    WebhookDelivery, WebhookDeliveryStatus, WebhookTimestamp,
+};
+
+mod webhook_delivery;
+
+#[derive(Clone)]
+pub struct WebhookDeliveryRepository {
+    db: DatabaseConnection,
+}
+
+impl WebhookDeliveryRepository {
+    pub fn new(db: DatabaseConnection) -> Self {
+        WebhookDeliveryRepository { db }
+    }
+
+    /// Inserts a new delivery and returns its id.
+    pub async fn create_delivery(&self, delivery: &WebhookDelivery) -> anyhow::Result<i32> {
+        let delivery_entity = webhook_delivery::ActiveModel {
+            id: NotSet,
+            ..map_active_model(delivery)
+        };
+        let inserted = delivery_entity
+            .insert(&self.db)
+            .await
+            .context("Could not insert webhook delivery into DB.")?;
+        Ok(inserted.id)
+    }
+
+    pub async fn update_delivery(&self, delivery: &WebhookDelivery) -> anyhow::Result<()> {
+        let id = delivery
+            .id
+            .context("Cannot update a webhook delivery without id.")?;
+        let delivery_entity = webhook_delivery::ActiveModel {
+            id: Set(id),
+            ..map_active_model(delivery)
+        };
+        delivery_entity
+            .update(&self.db)
+            .await
+            .context("Could not update webhook delivery in DB.")?;
+        Ok(())
+    }
+
+    /// Returns the most recent deliveries, newest first.
+    pub async fn get_deliveries(&self, limit: u64) -> anyhow::Result<Vec<WebhookDelivery>> {
+        let deliveries = webhook_delivery::Entity::find()
+            .order_by_desc(webhook_delivery::Column::Id)
+            .limit(limit)
+            .all(&self.db)
+            .await
+            .context("Could not load webhook deliveries from DB.")?;
+        deliveries
+            .into_iter()
+            .map(|model| {
+                let status = WebhookDeliveryStatus::from_str(&model.status)
+                    .map_err(|_| anyhow!("Could not parse webhook delivery status from DB."))?;
+                let created_at = DateTime::parse_from_rfc3339(&model.created_at)
+                    .context("Could not parse webhook delivery timestamp from DB.")?;
+                let updated_at = DateTime::parse_from_rfc3339(&model.updated_at)
+                    .context("Could not parse webhook delivery timestamp from DB.")?;
+                Ok(WebhookDelivery {
+                    id: Some(model.id),
+                    url: model.url,
+                    event_type: model.event_type,
+                    payload: model.payload,
+                    status,
+                    attempts: model.attempts as u32,
+                    response_status: model.response_status.map(|status| status as u16),
+                    last_error: model.last_error,
+                    created_at: WebhookTimestamp::from(created_at),
+                    updated_at: WebhookTimestamp::from(updated_at),
+                })
+            })
+            .collect::<anyhow::Result<Vec<WebhookDelivery>>>()
+            .context("Could not map DB entities to service entities.")
+    }
+
+    /// Deletes all but the given number of most recent deliveries to the url.
+    pub async fn prune_deliveries(&self, url: &str, keep: u64) -> anyhow::Result<()> {
+        let newest_pruned_id: Option<i32> = webhook_delivery::Entity::find()
+            .select_only()
+            .column(webhook_delivery::Column::Id)
+            .filter(webhook_delivery::Column::Url.eq(url))
+            .order_by_desc(webhook_delivery::Column::Id)
+            .offset(keep)
+            .into_tuple()
+            .one(&self.db)
+            .await
+            .context("Could not load webhook deliveries from DB.")?;
+        let Some(newest_pruned_id) = newest_pruned_id else {
+            return Ok(());
+        };
+        webhook_delivery::Entity::delete_many()
+            .filter(webhook_delivery::Column::Url.eq(url))
+            .filter(webhook_delivery::Column::Id.lte(newest_pruned_id))
+            .exec(&self.db)
+            .await
+            .context("Could not delete webhook deliveries from DB.")?;
+        Ok(())
+    }
+}
+
+fn map_active_model(delivery: &WebhookDelivery) -> webhook_delivery::ActiveModel {
+    webhook_delivery::ActiveModel {
+        id: NotSet,
+        url: Set(delivery.url.clone()),
+        event_type: Set(delivery.event_type.clone()),
+        payload: Set(delivery.payload.clone()),
+        status: Set(delivery.status.to_string()),
+        attempts: Set(delivery.attempts as i32),
+        response_status: Set(delivery.response_status.map(i32::from)),
+        last_error: Set(delivery.last_error.clone()),
+        created_at: Set(delivery.created_at.to_rfc3339()),
+        updated_at: Set(delivery.updated_at.to_rfc3339()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::{ConnectOptions, Database};
+
+    use crate::adapter::db::webhooks::WebhookDeliveryRepository;
+    use crate::service::notifications::model::{WebhookDelivery, WebhookDeliveryStatus};
+
+    async fn get_in_memory_repository() -> WebhookDeliveryRepository {
+        let connect_options = ConnectOptions::new("sqlite::memory:".to_owned());
+        let db_connection = Database::connect(connect_options).await.unwrap();
+        Migrator::up(&db_connection, None).await.unwrap();
+        WebhookDeliveryRepository::new(db_connection)
+    }
+
+    fn get_delivery(url: &str, payload: &str) -> WebhookDelivery {
+        WebhookDelivery {
+            id: None,
+            url: url.to_string(),
+            event_type: "pull_request".to_string(),
+            payload: payload.to_string(),
+            status: WebhookDeliveryStatus::Delivered,
+            attempts: 1,
+            response_status: Some(200),
+            last_error: None,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[tokio::test]
+    async fn prune_deliveries_keeps_most_recent_of_url() {
+        let repository = get_in_memory_repository().await;
+        for (url, payload) in [("a", "1"), ("b", "2"), ("a", "3"), ("a", "4")] {
+            repository
+                .create_delivery(&get_delivery(url, payload))
+                .await
+                .unwrap();
+        }
+
+        repository.prune_deliveries("a", 2).await.unwrap();
+
+        let payloads: Vec<String> = repository
+            .get_deliveries(10)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|delivery| delivery.payload)
+            .collect();
+        assert_eq!(vec!["4", "3", "2"], payloads);
+    }
+}
@@ -1,9 +1,19 @@
+use std::sync::Mutex;
+
 use anyhow::{anyhow, Context};
 use chrono::Utc;
 use reqwest::Method;
 
-use crate::adapter::gitlab::repositories::request::load_repository_data;
-use crate::service::repositories::model::{RepositoriesData, Repository};
+use crate::adapter::gitlab::repositories::model::PipelineResponse;
+use crate::adapter::gitlab::repositories::request::{
+    discover_group_projects, load_repository_data,
+};
+use crate::config::RepositoryDiscoveryConfiguration;
+use crate::service::repositories::branch_filter::BranchFilters;
+use crate::service::repositories::cache::{AheadBehindCache, RefreshCache};
+use crate::service::repositories::model::{
+    merge_repositories, RepositoriesData, Repository, RepositorySelector,
+};
 
 mod model;
 mod request;
@@ -12,16 +22,31 @@ pub struct GitlabClient {
     client: reqwest::Client,
     url: String,
     token: String,
-    repositories: Vec<Repository>,
+    repositories: Vec<RepositorySelector>,
+    discovery: RepositoryDiscoveryConfiguration,
+    branch_filters: BranchFilters,
+    ahead_behind_cache: Mutex<AheadBehindCache>,
+    // finished pipelines by ID and last update
+    pipeline_cache: Mutex<RefreshCache<(u32, Option<String>), PipelineResponse>>,
 }
 
 impl GitlabClient {
-    pub fn new(repositories: &[Repository], url: String, token: String) -> GitlabClient {
+    pub fn new(
+        repositories: &[RepositorySelector],
+        url: String,
+        token: String,
+        discovery: RepositoryDiscoveryConfiguration,
+        branch_filters: BranchFilters,
+    ) -> GitlabClient {
         GitlabClient {
             client: reqwest::Client::new(),
             url,
             token,
             repositories: Vec::from(repositories),
+            discovery,
+            branch_filters,
+            ahead_behind_cache: Mutex::new(AheadBehindCache::default()),
+            pipeline_cache: Mutex::new(RefreshCache::default()),
         }
     }
 
@@ -29,7 +54,39 @@ impl GitlabClient {
     where
         T: serde::de::DeserializeOwned,
     {
-        let full_url = format!("{}/api/v4/projects/{}", self.url, url);
+        self.request_api(&format!("projects/{url}")).await
+    }
+
+    /// Requests any resource of the API, e.g. `groups/...`.
+    pub async fn request_api<T>(&self, url: &str) -> anyhow::Result<T>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let parsed_body: T = self
+            .send(url)
+            .await?
+            .json()
+            .await
+            .context("Could not parse response body from JSON.")?;
+        Ok(parsed_body)
+    }
+
+    /// Requests the total number of items of a paginated list, which GitLab returns in the
+    /// `X-Total` header. Requesting a single item per page avoids loading the list.
+    pub async fn request_total(&self, url: &str) -> anyhow::Result<u32> {
+        let response = self.send(&format!("projects/{url}")).await?;
+        response
+            .headers()
+            .get("X-Total")
+            .ok_or_else(|| anyhow!("Missing total in response from gitlab for url {}.", url))?
+            .to_str()
+            .ok()
+            .and_then(|total| total.parse().ok())
+            .ok_or_else(|| anyhow!("Invalid total in response from gitlab for url {}.", url))
+    }
+
+    async fn send(&self, url: &str) -> anyhow::Result<reqwest::Response> {
+        let full_url = format!("{}/api/v4/{}", self.url, url);
         let response = self
             .client
             .request(Method::GET, full_url)
@@ -47,28 +104,52 @@ impl GitlabClient {
                 response.status()
             ));
         };
+        Ok(response)
+    }
 
-        let parsed_body: T = response
-            .json()
-            .await
-            .context("Could not parse response body from JSON.")?;
-        Ok(parsed_body)
+    /// Resolves the configured repositories, discovering the projects of configured groups.
+    async fn get_repositories(&self) -> anyhow::Result<Vec<Repository>> {
+        let mut repositories = Vec::new();
+        for selector in &self.repositories {
+            match selector {
+                RepositorySelector::Repository(repository) => {
+                    merge_repositories(&mut repositories, [repository.clone()])
+                }
+                RepositorySelector::Namespace {
+                    namespace,
+                    include_subgroups,
+                } => {
+                    let discovered = discover_group_projects(
+                        self,
+                        namespace,
+                        *include_subgroups,
+                        &self.discovery,
+                    )
+                    .await?;
+                    merge_repositories(&mut repositories, discovered);
+                }
+            }
+        }
+        Ok(repositories)
     }
 
     pub async fn load_repositories_data(&self) -> anyhow::Result<RepositoriesData> {
         let mut repositories = Vec::new();
-        for repository in &self.repositories {
+        for repository in &self.get_repositories().await? {
             let repository_data = load_repository_data(self, repository)
                 .await
                 .with_context(|| format!("Could not load data for repository {repository}."))?;
             repositories.push(repository_data);
         }
+        self.ahead_behind_cache.lock().unwrap().finish_refresh();
+        self.pipeline_cache.lock().unwrap().finish_refresh();
 
         let last_updated_date = Utc::now().format("%+").to_string();
         Ok(RepositoriesData {
             last_updated_date: Some(last_updated_date),
             repositories,
             currently_refreshing: false,
+            stale: false,
         })
     }
 }
@@ -1,14 +1,16 @@
 use std::fmt;
 use std::fmt::Formatter;
 
+use serde::de::IgnoredAny;
 use serde_derive::Deserialize;
 
-use crate::service::repositories::model::Repository;
+use crate::service::repositories::model::{AheadBehind, Repository};
 
 pub struct ProjectDetails {
     pub id: u32,
     pub repository: Repository,
     pub url: String,
+    pub default_branch: Option<String>,
 }
 
 impl fmt::Display for ProjectDetails {
@@ -20,24 +22,54 @@ impl fmt::Display for ProjectDetails {
 pub struct BranchDetails {
     pub details_response: BranchResponse,
     pub pipeline_response: Option<PipelineResponse>,
-    pub job_response: Option<JobResponse>,
+    // newest first, only the latest job unless all of them are of interest
+    pub job_responses: Vec<JobResponse>,
+    pub ahead_behind: Option<AheadBehind>,
 }
 
 pub struct MergeRequestDetails {
     pub details_response: SingleMergeRequestResponse,
     pub approvals_response: MergeRequestApprovalsResponse,
-    pub job_response: Option<JobResponse>,
+    pub reviewers_response: Vec<MergeRequestReviewerResponse>,
+    // newest first, only the latest job unless all of them are of interest
+    pub job_responses: Vec<JobResponse>,
+    pub ahead_behind: Option<AheadBehind>,
 }
 
 #[derive(Deserialize)]
 pub struct ProjectResponse {
     pub id: u32,
     pub web_url: String,
+    // not set for empty projects
+    pub default_branch: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct MergeBaseResponse {
+    pub id: String,
+}
+
+#[derive(Deserialize)]
+pub struct GroupProjectResponse {
+    pub path_with_namespace: String,
+    #[serde(default)]
+    pub archived: bool,
+    // only present for forks
+    pub forked_from_project: Option<IgnoredAny>,
 }
 
 #[derive(Deserialize)]
 pub struct BranchResponse {
     pub name: String,
+    pub commit: Option<BranchCommitResponse>,
+}
+
+#[derive(Deserialize)]
+pub struct BranchCommitResponse {
+    pub id: String,
+    pub title: String,
+    pub author_name: String,
+    pub committed_date: String,
 }
 
 #[derive(Deserialize)]
@@ -47,38 +79,99 @@ pub struct MergeRequestResponse {
 
 #[derive(Deserialize)]
 pub struct SingleMergeRequestResponse {
+    pub iid: u32,
+    pub title: String,
+    pub description: Option<String>,
+    // replaces work_in_progress, which was removed in API v5
+    #[serde(default)]
+    pub draft: bool,
     pub source_branch: String,
     pub target_branch: String,
+    // head of the source branch
+    pub sha: Option<String>,
     pub author: GitlabUserResponse,
+    #[serde(default)]
+    pub reviewers: Vec<GitlabReviewerResponse>,
+    pub created_at: String,
     pub updated_at: String,
     pub user_notes_count: u32,
+    // deprecated in favor of detailed_merge_status, which is only present on GitLab 15.6 and later
+    pub merge_status: Option<String>,
+    pub detailed_merge_status: Option<String>,
+    #[serde(default)]
+    pub has_conflicts: bool,
+    #[serde(default = "default_true")]
+    pub blocking_discussions_resolved: bool,
     pub pipeline: Option<PipelineResponse>,
     pub web_url: String,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, Clone)]
 pub struct PipelineResponse {
     pub id: u32,
     pub status: GitlabPipelineStatus,
+    pub sha: String,
+    pub created_at: Option<String>,
+    // changes when the pipeline is retried
+    pub updated_at: Option<String>,
+    // the timing is only part of the response for a single pipeline
+    #[serde(default)]
+    pub started_at: Option<String>,
+    #[serde(default)]
+    pub finished_at: Option<String>,
+    // in seconds
+    #[serde(default)]
+    pub duration: Option<u64>,
 }
 
 #[derive(Deserialize)]
 pub struct JobResponse {
+    pub name: String,
+    pub stage: String,
+    pub status: GitlabPipelineStatus,
+    // in seconds, not set for jobs which have not started
+    pub duration: Option<f64>,
+    #[serde(default)]
+    pub allow_failure: bool,
     pub web_url: String,
 }
 
 #[derive(Deserialize)]
 pub struct MergeRequestApprovalsResponse {
     pub approved: bool,
+    // only set by approval rules of GitLab Premium
+    #[serde(default)]
+    pub approvals_required: u32,
+    #[serde(default)]
+    pub approved_by: Vec<ApprovalResponse>,
+}
+
+#[derive(Deserialize)]
+pub struct MergeRequestReviewerResponse {
+    pub user: GitlabReviewerResponse,
+    // e.g. unreviewed, reviewed, requested_changes, only present on GitLab 16.8 and later
+    pub state: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ApprovalResponse {
+    pub user: GitlabReviewerResponse,
 }
 
 #[derive(Deserialize)]
 pub struct GitlabUserResponse {
+    pub username: String,
     pub name: String,
     pub avatar_url: String,
 }
 
 #[derive(Deserialize)]
+pub struct GitlabReviewerResponse {
+    pub username: String,
+    pub name: String,
+}
+
+#[derive(Deserialize, Clone, Copy)]
 pub enum GitlabPipelineStatus {
     #[serde(rename = "success")]
     Success,
@@ -94,6 +187,8 @@ pub enum GitlabPipelineStatus {
     Preparing,
     #[serde(rename = "pending")]
     Pending,
+    #[serde(rename = "canceling")]
+    Canceling,
     #[serde(rename = "canceled")]
     Canceled,
     #[serde(rename = "skipped")]
@@ -102,4 +197,23 @@ pub enum GitlabPipelineStatus {
     Manual,
     #[serde(rename = "scheduled")]
     Scheduled,
+    // also covers statuses added by future versions, which must not abort the refresh
+    #[serde(other)]
+    Unknown,
+}
+
+impl GitlabPipelineStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            GitlabPipelineStatus::Success
+                | GitlabPipelineStatus::Failed
+                | GitlabPipelineStatus::Canceled
+                | GitlabPipelineStatus::Skipped
+        )
+    }
+}
+
+fn default_true() -> bool {
+    true
 }
@@ -1,18 +1,26 @@
 use std::collections::HashSet;
 
 use anyhow::Context;
+use log::warn;
 
 use crate::adapter::gitlab::repositories::model::{
-    BranchDetails, BranchResponse, GitlabPipelineStatus, JobResponse,
-    MergeRequestApprovalsResponse, MergeRequestDetails, MergeRequestResponse, PipelineResponse,
-    ProjectDetails, ProjectResponse, SingleMergeRequestResponse,
+    BranchDetails, BranchResponse, GitlabPipelineStatus, GroupProjectResponse, JobResponse,
+    MergeBaseResponse, MergeRequestApprovalsResponse, MergeRequestDetails, MergeRequestResponse,
+    MergeRequestReviewerResponse, PipelineResponse, ProjectDetails, ProjectResponse,
+    SingleMergeRequestResponse,
 };
 use crate::adapter::gitlab::repositories::GitlabClient;
+use crate::config::RepositoryDiscoveryConfiguration;
+use crate::service::repositories::branch_filter::parse_commit_date;
 use crate::service::repositories::model::{
-    PipelineStatus, PullRequest, PullRequestTargetBranch, Repository, RepositoryBranchData,
-    StandaloneBranch,
+    AheadBehind, Commit, MergeBlocker, MergeBlockerKind, Mergeability, PipelineJob, PipelineStatus,
+    PipelineTiming, PullRequest, PullRequestTargetBranch, Repository, RepositoryBranchData,
+    ReviewSummary, Reviewer, StandaloneBranch,
 };
 
+const DISCOVERY_PAGE_SIZE: usize = 100;
+const JOBS_PAGE_SIZE: usize = 100;
+
 pub async fn load_repository_data(
     client: &GitlabClient,
     repository: &Repository,
@@ -22,9 +30,11 @@ pub async fn load_repository_data(
         id: project_response.id,
         repository: repository.clone(),
         url: project_response.web_url,
+        default_branch: project_response.default_branch,
     };
-    let merge_request_details = get_merge_requests(client, &project).await?;
-    let branch_details = get_branches(client, &project).await?;
+    let branches = get_branch_list(client, &project).await?;
+    let merge_request_details = get_merge_requests(client, &project, &branches).await?;
+    let branch_details = get_branches(client, &project, branches, &merge_request_details).await?;
 
     let repository_branch_data =
         map_repository_data(&project, merge_request_details, branch_details)?;
@@ -32,6 +42,44 @@ pub async fn load_repository_data(
     Ok(repository_branch_data)
 }
 
+/// Lists the projects of a group, following the pagination of the API.
+pub async fn discover_group_projects(
+    client: &GitlabClient,
+    group: &str,
+    include_subgroups: bool,
+    discovery: &RepositoryDiscoveryConfiguration,
+) -> anyhow::Result<Vec<Repository>> {
+    let mut projects: Vec<GroupProjectResponse> = Vec::new();
+    for page in 1.. {
+        let mut url = format!(
+            "groups/{}/projects?include_subgroups={}&per_page={}&page={}",
+            encode_id_for_gitlab_url(group),
+            include_subgroups,
+            DISCOVERY_PAGE_SIZE,
+            page
+        );
+        if discovery.exclude_archived {
+            url.push_str("&archived=false");
+        }
+        let page_projects: Vec<GroupProjectResponse> = client
+            .request_api(&url)
+            .await
+            .with_context(|| format!("Could not load projects of group {group}."))?;
+        let is_last_page = page_projects.len() < DISCOVERY_PAGE_SIZE;
+        projects.extend(page_projects);
+        if is_last_page {
+            break;
+        }
+    }
+
+    projects
+        .into_iter()
+        .filter(|project| !(discovery.exclude_archived && project.archived))
+        .filter(|project| !(discovery.exclude_forks && project.forked_from_project.is_some()))
+        .map(|project| Repository::from_slug(&project.path_with_namespace))
+        .collect()
+}
+
 async fn get_project(
     client: &GitlabClient,
     repository: &Repository,
@@ -46,6 +94,7 @@ async fn get_project(
 async fn get_merge_requests(
     client: &GitlabClient,
     project: &ProjectDetails,
+    branches: &[BranchResponse],
 ) -> anyhow::Result<Vec<MergeRequestDetails>> {
     let merqe_requests: Vec<MergeRequestResponse> = client
         .request(&format!("{}/merge_requests?state=opened", project.id))
@@ -60,7 +109,7 @@ async fn get_merge_requests(
     // merge request details
     let mut merge_request_details = Vec::new();
     for merge_request in merqe_requests {
-        let single_merge_request_response: SingleMergeRequestResponse = client
+        let mut single_merge_request_response: SingleMergeRequestResponse = client
             .request(&format!(
                 "{}/merge_requests/{}",
                 project.id, merge_request.iid
@@ -85,15 +134,33 @@ async fn get_merge_requests(
                 )
             })?;
 
-        let latest_pipeline_job = match &single_merge_request_response.pipeline {
-            Some(pipeline) => get_latest_pipeline_job(client, project, pipeline.id).await?,
-            None => None,
+        let merge_request_reviewers =
+            get_merge_request_reviewers(client, project, merge_request.iid).await;
+
+        if let Some(pipeline) = single_merge_request_response.pipeline.take() {
+            single_merge_request_response.pipeline =
+                Some(get_pipeline_timing(client, project, pipeline).await?);
+        }
+        let pipeline_jobs = match &single_merge_request_response.pipeline {
+            Some(pipeline) => get_pipeline_jobs(client, project, pipeline).await?,
+            None => Vec::new(),
+        };
+
+        let target_commit =
+            get_branch_commit(branches, &single_merge_request_response.target_branch);
+        let ahead_behind = match (&single_merge_request_response.sha, target_commit) {
+            (Some(source_commit), Some(target_commit)) => {
+                get_ahead_behind(client, project, source_commit, target_commit).await
+            }
+            _ => None,
         };
 
         let merge_request_detail = MergeRequestDetails {
             details_response: single_merge_request_response,
             approvals_response: merge_request_approvals,
-            job_response: latest_pipeline_job,
+            reviewers_response: merge_request_reviewers,
+            job_responses: pipeline_jobs,
+            ahead_behind,
         };
 
         merge_request_details.push(merge_request_detail);
@@ -102,11 +169,33 @@ async fn get_merge_requests(
     Ok(merge_request_details)
 }
 
-async fn get_branches(
+/// Loads the review states of the reviewers. Falls back to no states on older GitLab versions,
+/// where only approvals are known.
+async fn get_merge_request_reviewers(
     client: &GitlabClient,
     project: &ProjectDetails,
-) -> anyhow::Result<Vec<BranchDetails>> {
-    let branches: Vec<BranchResponse> = client
+    merge_request_iid: u32,
+) -> Vec<MergeRequestReviewerResponse> {
+    client
+        .request(&format!(
+            "{}/merge_requests/{}/reviewers",
+            project.id, merge_request_iid
+        ))
+        .await
+        .unwrap_or_else(|err| {
+            warn!(
+                "Could not load merge request reviewers for project {} and MR {}: {:#}",
+                project.repository, merge_request_iid, err
+            );
+            Vec::new()
+        })
+}
+
+async fn get_branch_list(
+    client: &GitlabClient,
+    project: &ProjectDetails,
+) -> anyhow::Result<Vec<BranchResponse>> {
+    client
         .request(&format!("{}/repository/branches", project.id))
         .await
         .with_context(|| {
@@ -114,10 +203,53 @@ async fn get_branches(
                 "Could not load branches for project: {}",
                 project.repository
             )
-        })?;
+        })
+}
+
+fn get_branch_commit<'a>(branches: &'a [BranchResponse], branch_name: &str) -> Option<&'a str> {
+    branches
+        .iter()
+        .find(|branch| branch.name == branch_name)
+        .and_then(|branch| branch.commit.as_ref())
+        .map(|commit| commit.id.as_str())
+}
+
+async fn get_branches(
+    client: &GitlabClient,
+    project: &ProjectDetails,
+    branches: Vec<BranchResponse>,
+    merge_requests: &[MergeRequestDetails],
+) -> anyhow::Result<Vec<BranchDetails>> {
+    let default_branch_commit = project
+        .default_branch
+        .as_ref()
+        .and_then(|default_branch| get_branch_commit(&branches, default_branch))
+        .map(str::to_string);
+
+    let is_merge_request_branch = |branch_name: &str| {
+        merge_requests.iter().any(|mr| {
+            mr.details_response.source_branch.eq(branch_name)
+                || mr.details_response.target_branch.eq(branch_name)
+        })
+    };
+
+    // hidden branches are skipped before loading their pipelines
+    let filter = client
+        .branch_filters
+        .get_filter(&project.repository.to_string());
+    let is_shown = |branch: &BranchResponse| {
+        is_merge_request_branch(&branch.name)
+            || filter.is_shown(
+                &branch.name,
+                branch
+                    .commit
+                    .as_ref()
+                    .and_then(|commit| parse_commit_date(&commit.committed_date)),
+            )
+    };
 
     let mut branch_details = Vec::new();
-    for branch in branches.into_iter() {
+    for branch in branches.into_iter().filter(is_shown) {
         let encoded_branch = encode_id_for_gitlab_url(&branch.name);
         let pipelines_response: Vec<PipelineResponse> = client
             .request(&format!(
@@ -131,16 +263,30 @@ async fn get_branches(
                     project.repository, branch.name,
                 )
             })?;
-        let pipeline_response = pipelines_response.into_iter().next();
-        let job_response = match &pipeline_response {
-            Some(pipeline) => get_latest_pipeline_job(client, project, pipeline.id).await?,
+        let pipeline_response = match pipelines_response.into_iter().next() {
+            Some(pipeline) => Some(get_pipeline_timing(client, project, pipeline).await?),
             None => None,
         };
+        let job_responses = match &pipeline_response {
+            Some(pipeline) => get_pipeline_jobs(client, project, pipeline).await?,
+            None => Vec::new(),
+        };
+
+        // only needed for standalone branches, compared to the default branch
+        let ahead_behind = match (&branch.commit, &default_branch_commit) {
+            (Some(commit), Some(default_branch_commit))
+                if !is_merge_request_branch(&branch.name) =>
+            {
+                get_ahead_behind(client, project, &commit.id, default_branch_commit).await
+            }
+            _ => None,
+        };
 
         let single_branch_details = BranchDetails {
             pipeline_response,
             details_response: branch,
-            job_response,
+            job_responses,
+            ahead_behind,
         };
         branch_details.push(single_branch_details);
     }
@@ -148,25 +294,297 @@ async fn get_branches(
     Ok(branch_details)
 }
 
-async fn get_latest_pipeline_job(
+/// Counts the commits of the source which are not in the target and vice versa. The counts are
+/// only informative, so they are left out if the commits cannot be compared.
+async fn get_ahead_behind(
+    client: &GitlabClient,
+    project: &ProjectDetails,
+    source_commit: &str,
+    target_commit: &str,
+) -> Option<AheadBehind> {
+    let key = (source_commit.to_string(), target_commit.to_string());
+    if let Some(ahead_behind) = client.ahead_behind_cache.lock().unwrap().get(&key) {
+        return Some(ahead_behind);
+    }
+
+    match count_ahead_behind(client, project, source_commit, target_commit).await {
+        Ok(ahead_behind) => {
+            client
+                .ahead_behind_cache
+                .lock()
+                .unwrap()
+                .insert(key, ahead_behind);
+            Some(ahead_behind)
+        }
+        Err(err) => {
+            warn!(
+                "Could not compare commits {} and {} of project {}: {:#}",
+                source_commit, target_commit, project.repository, err
+            );
+            None
+        }
+    }
+}
+
+async fn count_ahead_behind(
+    client: &GitlabClient,
+    project: &ProjectDetails,
+    source_commit: &str,
+    target_commit: &str,
+) -> anyhow::Result<AheadBehind> {
+    let merge_base: MergeBaseResponse = client
+        .request(&format!(
+            "{}/repository/merge_base?refs[]={}&refs[]={}",
+            project.id, source_commit, target_commit
+        ))
+        .await
+        .with_context(|| {
+            format!(
+                "Could not find merge base of commits {} and {} of project {}.",
+                source_commit, target_commit, project.repository
+            )
+        })?;
+    Ok(AheadBehind {
+        ahead: count_commits_since(client, project, &merge_base.id, source_commit).await?,
+        behind: count_commits_since(client, project, &merge_base.id, target_commit).await?,
+    })
+}
+
+/// Counts the commits of `commit` which are not in its ancestor `base`, without loading them.
+async fn count_commits_since(
+    client: &GitlabClient,
+    project: &ProjectDetails,
+    base: &str,
+    commit: &str,
+) -> anyhow::Result<u32> {
+    if base == commit {
+        return Ok(0);
+    }
+    client
+        .request_total(&format!(
+            "{}/repository/commits?ref_name={}..{}&per_page=1",
+            project.id, base, commit
+        ))
+        .await
+        .with_context(|| {
+            format!(
+                "Could not count commits between {} and {} of project {}.",
+                base, commit, project.repository
+            )
+        })
+}
+
+/// Adds the timing to a pipeline from a list by loading the single pipeline. The timing is only
+/// recorded for finished pipelines, which are loaded once and reused until they are retried.
+async fn get_pipeline_timing(
+    client: &GitlabClient,
+    project: &ProjectDetails,
+    pipeline: PipelineResponse,
+) -> anyhow::Result<PipelineResponse> {
+    if !pipeline.status.is_finished() {
+        return Ok(pipeline);
+    }
+    let key = (pipeline.id, pipeline.updated_at.clone());
+    if let Some(cached_pipeline) = client.pipeline_cache.lock().unwrap().get(&key) {
+        return Ok(cached_pipeline);
+    }
+    let single_pipeline = get_pipeline(client, project, pipeline.id).await?;
+    client
+        .pipeline_cache
+        .lock()
+        .unwrap()
+        .insert(key, single_pipeline.clone());
+    Ok(single_pipeline)
+}
+
+/// Loads a single pipeline, which unlike the lists includes its timing.
+async fn get_pipeline(
     client: &GitlabClient,
     project: &ProjectDetails,
     pipeline_id: u32,
-) -> anyhow::Result<Option<JobResponse>> {
-    let jobs: Vec<JobResponse> = client
+) -> anyhow::Result<PipelineResponse> {
+    client
+        .request(&format!("{}/pipelines/{}", project.id, pipeline_id))
+        .await
+        .with_context(|| {
+            format!(
+                "Could not load pipeline {} of project {}.",
+                pipeline_id, project.repository
+            )
+        })
+}
+
+/// Loads the jobs of a pipeline, newest first. Only the latest job is needed for the link to the
+/// pipeline, unless its status makes all of its jobs of interest.
+async fn get_pipeline_jobs(
+    client: &GitlabClient,
+    project: &ProjectDetails,
+    pipeline: &PipelineResponse,
+) -> anyhow::Result<Vec<JobResponse>> {
+    let per_page = if map_gitlab_status(&pipeline.status).has_relevant_jobs() {
+        JOBS_PAGE_SIZE
+    } else {
+        1
+    };
+    client
         .request(&format!(
-            "{}/pipelines/{}/jobs?per_page=1",
-            project.id, pipeline_id
+            "{}/pipelines/{}/jobs?per_page={}",
+            project.id, pipeline.id, per_page
         ))
         .await
         .with_context(|| {
             format!(
                 "Could not load jobs for project {} and pipeline {}.",
-                project.repository, pipeline_id
+                project.repository, pipeline.id
             )
-        })?;
-    let latest_job = jobs.into_iter().next();
-    Ok(latest_job)
+        })
+}
+
+fn map_last_commit(branch: &BranchResponse) -> Option<Commit> {
+    branch.commit.as_ref().map(|commit| Commit {
+        sha: commit.id.to_owned(),
+        author: commit.author_name.to_owned(),
+        message_headline: Commit::get_message_headline(&commit.title),
+        date: commit.committed_date.to_owned(),
+    })
+}
+
+/// Combines the assigned reviewers with the users who approved, who do not have to be assigned.
+fn map_reviewers(merge_request: &MergeRequestDetails) -> Vec<Reviewer> {
+    let approvers = &merge_request.approvals_response.approved_by;
+    let is_approver = |username: &str| {
+        approvers
+            .iter()
+            .any(|approval| approval.user.username == username)
+    };
+    let has_requested_changes = |username: &str| {
+        merge_request.reviewers_response.iter().any(|reviewer| {
+            reviewer.user.username == username
+                && reviewer.state.as_deref() == Some("requested_changes")
+        })
+    };
+
+    let mut reviewers: Vec<Reviewer> = merge_request
+        .details_response
+        .reviewers
+        .iter()
+        .map(|reviewer| Reviewer {
+            user_id: reviewer.username.to_owned(),
+            user_name: reviewer.name.to_owned(),
+            approved: is_approver(&reviewer.username),
+            changes_requested: has_requested_changes(&reviewer.username),
+        })
+        .collect();
+    for approval in approvers {
+        if !reviewers
+            .iter()
+            .any(|reviewer| reviewer.user_id == approval.user.username)
+        {
+            reviewers.push(Reviewer {
+                user_id: approval.user.username.to_owned(),
+                user_name: approval.user.name.to_owned(),
+                approved: true,
+                changes_requested: false,
+            });
+        }
+    }
+    reviewers
+}
+
+/// Relies on GitLab to decide whether the approval rules are satisfied if there are any.
+fn map_review_summary(merge_request: &MergeRequestDetails) -> ReviewSummary {
+    let approvals = &merge_request.approvals_response;
+    let approvals_required = Some(approvals.approvals_required).filter(|required| *required > 0);
+    let mut review_summary = ReviewSummary::new(&map_reviewers(merge_request), approvals_required);
+    if approvals_required.is_some() {
+        review_summary.approved =
+            approvals.approved && review_summary.changes_requested_by.is_empty();
+    }
+    review_summary
+}
+
+/// Maps the merge status of a merge request, preferring the detailed one of newer GitLab versions.
+/// Returns `None` while GitLab has not checked the merge request yet.
+fn map_mergeability(merge_request: &SingleMergeRequestResponse) -> Option<Mergeability> {
+    let status = merge_request
+        .detailed_merge_status
+        .as_deref()
+        .or(merge_request.merge_status.as_deref())?;
+    let can_merge = match status {
+        "unchecked"
+        | "checking"
+        | "preparing"
+        | "approvals_syncing"
+        | "cannot_be_merged_recheck" => return None,
+        "mergeable" | "can_be_merged" => true,
+        _ => false,
+    };
+
+    let mut blockers = Vec::new();
+    if !can_merge {
+        let (kind, message) = match status {
+            "conflict" => (
+                MergeBlockerKind::Conflicts,
+                "The merge request has merge conflicts.",
+            ),
+            "cannot_be_merged" if merge_request.has_conflicts => (
+                MergeBlockerKind::Conflicts,
+                "The merge request has merge conflicts.",
+            ),
+            "cannot_be_merged" => (
+                MergeBlockerKind::Other,
+                "The merge request cannot be merged.",
+            ),
+            "not_approved" => (
+                MergeBlockerKind::Approvals,
+                "The merge request is not approved yet.",
+            ),
+            "requested_changes" => (MergeBlockerKind::Approvals, "A reviewer requested changes."),
+            "discussions_not_resolved" => (
+                MergeBlockerKind::UnresolvedThreads,
+                "Not all threads are resolved.",
+            ),
+            "draft_status" => (MergeBlockerKind::Draft, "The merge request is a draft."),
+            "ci_must_pass" => (MergeBlockerKind::Pipeline, "The pipeline has to succeed."),
+            "ci_still_running" => (MergeBlockerKind::Pipeline, "The pipeline is still running."),
+            "need_rebase" => (
+                MergeBlockerKind::Other,
+                "The source branch has to be rebased.",
+            ),
+            other => (MergeBlockerKind::Other, other),
+        };
+        blockers.push(MergeBlocker {
+            kind,
+            message: message.to_string(),
+        });
+    }
+    // the detailed status only names one reason, while these are known independently of it
+    if merge_request.has_conflicts
+        && !blockers
+            .iter()
+            .any(|blocker| blocker.kind == MergeBlockerKind::Conflicts)
+    {
+        blockers.push(MergeBlocker {
+            kind: MergeBlockerKind::Conflicts,
+            message: "The merge request has merge conflicts.".to_string(),
+        });
+    }
+    if !merge_request.blocking_discussions_resolved
+        && !blockers
+            .iter()
+            .any(|blocker| blocker.kind == MergeBlockerKind::UnresolvedThreads)
+    {
+        blockers.push(MergeBlocker {
+            kind: MergeBlockerKind::UnresolvedThreads,
+            message: "Not all threads are resolved.".to_string(),
+        });
+    }
+
+    Some(Mergeability {
+        can_merge: can_merge && blockers.is_empty(),
+        has_conflicts: merge_request.has_conflicts,
+        blockers,
+    })
 }
 
 fn map_repository_data(
@@ -186,13 +604,30 @@ fn map_repository_data(
                     .iter()
                     .filter(|pr| pr.details_response.target_branch.eq(*name))
                     .map(|pr| PullRequest {
+                        number: pr.details_response.iid,
+                        title: pr.details_response.title.to_owned(),
+                        description: pr.details_response.description.to_owned(),
+                        draft: pr.details_response.draft,
                         branch_name: pr.details_response.source_branch.to_owned(),
                         user_name: pr.details_response.author.name.to_owned(),
-                        pipeline_status: map_pipeline_status(&pr.details_response.pipeline),
-                        pipeline_url: pr.job_response.as_ref().map(|job| job.web_url.to_owned()),
+                        user_id: pr.details_response.author.username.to_owned(),
+                        pipeline_status: map_pipeline_status(
+                            &pr.details_response.pipeline,
+                            &pr.job_responses,
+                        ),
+                        pipeline_url: pr.job_responses.first().map(|job| job.web_url.to_owned()),
+                        pipeline_jobs: map_pipeline_jobs(
+                            &pr.details_response.pipeline,
+                            &pr.job_responses,
+                        ),
+                        pipeline_timing: map_pipeline_timing(&pr.details_response.pipeline),
                         comment_count: pr.details_response.user_notes_count,
-                        approved: pr.approvals_response.approved,
+                        review: map_review_summary(pr),
+                        reviewers: map_reviewers(pr),
+                        ahead_behind: pr.ahead_behind,
+                        mergeability: map_mergeability(&pr.details_response),
                         user_profile_image: pr.details_response.author.avatar_url.to_owned(),
+                        created_date: pr.details_response.created_at.to_owned(),
                         last_activity_date: pr.details_response.updated_at.to_owned(),
                         link_url: pr.details_response.web_url.to_owned(),
                     })
@@ -203,11 +638,20 @@ fn map_repository_data(
                     .with_context(|| format!("Could not find branch details for branch {name}."))?;
                 Ok(PullRequestTargetBranch {
                     branch_name: name.to_string(),
-                    pipeline_status: map_pipeline_status(&target_branch_details.pipeline_response),
+                    pipeline_status: map_pipeline_status(
+                        &target_branch_details.pipeline_response,
+                        &target_branch_details.job_responses,
+                    ),
                     pipeline_url: target_branch_details
-                        .job_response
-                        .as_ref()
+                        .job_responses
+                        .first()
                         .map(|job| job.web_url.to_owned()),
+                    pipeline_jobs: map_pipeline_jobs(
+                        &target_branch_details.pipeline_response,
+                        &target_branch_details.job_responses,
+                    ),
+                    pipeline_timing: map_pipeline_timing(&target_branch_details.pipeline_response),
+                    last_commit: map_last_commit(&target_branch_details.details_response),
                     pull_requests,
                 })
             })
@@ -229,11 +673,15 @@ fn map_repository_data(
         })
         .map(|branch| StandaloneBranch {
             branch_name: branch.details_response.name.to_string(),
-            pipeline_status: map_pipeline_status(&branch.pipeline_response),
+            pipeline_status: map_pipeline_status(&branch.pipeline_response, &branch.job_responses),
             pipeline_url: branch
-                .job_response
-                .as_ref()
+                .job_responses
+                .first()
                 .map(|job| job.web_url.to_owned()),
+            pipeline_jobs: map_pipeline_jobs(&branch.pipeline_response, &branch.job_responses),
+            pipeline_timing: map_pipeline_timing(&branch.pipeline_response),
+            last_commit: map_last_commit(&branch.details_response),
+            ahead_behind: branch.ahead_behind,
         })
         .collect();
 
@@ -251,24 +699,341 @@ fn encode_id_for_gitlab_url(id: &str) -> String {
 }
 
 fn encode_repository_for_gitlab_url(repository: &Repository) -> String {
-    format!("{}%2F{}", repository.group, repository.name)
+    encode_id_for_gitlab_url(&repository.to_string())
 }
 
-fn map_pipeline_status(response: &Option<PipelineResponse>) -> PipelineStatus {
+/// GitLab reports pipelines waiting for a manual job as manual, and only tells whether the job
+/// blocks the pipeline on the job itself.
+fn map_pipeline_status(
+    response: &Option<PipelineResponse>,
+    jobs: &[JobResponse],
+) -> PipelineStatus {
     match response {
-        Some(response) => match response.status {
-            GitlabPipelineStatus::Running => PipelineStatus::Running,
-            GitlabPipelineStatus::Success => PipelineStatus::Successful,
-            GitlabPipelineStatus::Failed => PipelineStatus::Failed,
-            GitlabPipelineStatus::Created => PipelineStatus::Queued,
-            GitlabPipelineStatus::WaitingForResource => PipelineStatus::Queued,
-            GitlabPipelineStatus::Preparing => PipelineStatus::Queued,
-            GitlabPipelineStatus::Pending => PipelineStatus::Queued,
-            GitlabPipelineStatus::Canceled => PipelineStatus::Canceled,
-            GitlabPipelineStatus::Skipped => PipelineStatus::None,
-            GitlabPipelineStatus::Manual => PipelineStatus::None,
-            GitlabPipelineStatus::Scheduled => PipelineStatus::Queued,
+        Some(response) => match map_gitlab_status(&response.status) {
+            PipelineStatus::Manual
+                if jobs.iter().any(|job| {
+                    map_gitlab_status(&job.status) == PipelineStatus::Manual && !job.allow_failure
+                }) =>
+            {
+                PipelineStatus::Blocked
+            }
+            status => status,
         },
         None => PipelineStatus::None,
     }
 }
+
+/// Jobs share their statuses with pipelines.
+fn map_gitlab_status(status: &GitlabPipelineStatus) -> PipelineStatus {
+    match status {
+        GitlabPipelineStatus::Running => PipelineStatus::Running,
+        GitlabPipelineStatus::Success => PipelineStatus::Successful,
+        GitlabPipelineStatus::Failed => PipelineStatus::Failed,
+        GitlabPipelineStatus::Created => PipelineStatus::Queued,
+        GitlabPipelineStatus::WaitingForResource => PipelineStatus::Queued,
+        GitlabPipelineStatus::Preparing => PipelineStatus::Queued,
+        GitlabPipelineStatus::Pending => PipelineStatus::Queued,
+        GitlabPipelineStatus::Canceling => PipelineStatus::Canceled,
+        GitlabPipelineStatus::Canceled => PipelineStatus::Canceled,
+        GitlabPipelineStatus::Skipped => PipelineStatus::Skipped,
+        GitlabPipelineStatus::Manual => PipelineStatus::Manual,
+        GitlabPipelineStatus::Scheduled => PipelineStatus::Scheduled,
+        GitlabPipelineStatus::Unknown => PipelineStatus::None,
+    }
+}
+
+fn map_pipeline_timing(pipeline: &Option<PipelineResponse>) -> Option<PipelineTiming> {
+    pipeline.as_ref().map(|pipeline| PipelineTiming {
+        commit_sha: pipeline.sha.to_owned(),
+        created_date: pipeline.created_at.to_owned(),
+        started_date: pipeline.started_at.to_owned(),
+        finished_date: pipeline.finished_at.to_owned(),
+        duration_seconds: pipeline.duration,
+    })
+}
+
+fn map_pipeline_jobs(
+    pipeline: &Option<PipelineResponse>,
+    jobs: &[JobResponse],
+) -> Vec<PipelineJob> {
+    if !map_pipeline_status(pipeline, jobs).has_relevant_jobs() {
+        return Vec::new();
+    }
+    jobs.iter()
+        .map(|job| PipelineJob {
+            name: job.name.to_owned(),
+            stage: Some(job.stage.to_owned()),
+            status: map_gitlab_status(&job.status),
+            duration_seconds: job.duration.map(|duration| duration.round() as u64),
+            url: Some(job.web_url.to_owned()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    use axum::extract::Query;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use axum::{Json, Router};
+    use chrono::{Duration, Utc};
+    use serde_json::json;
+
+    use crate::adapter::gitlab::repositories::model::{
+        BranchResponse, GitlabPipelineStatus, PipelineResponse, ProjectDetails,
+        SingleMergeRequestResponse,
+    };
+    use crate::adapter::gitlab::repositories::request::{
+        encode_repository_for_gitlab_url, get_ahead_behind, get_branches, get_pipeline_timing,
+        map_gitlab_status, map_mergeability,
+    };
+    use crate::adapter::gitlab::repositories::GitlabClient;
+    use crate::config::{
+        BranchFilterConfiguration, BranchFiltersConfiguration, RepositoryDiscoveryConfiguration,
+    };
+    use crate::service::repositories::branch_filter::BranchFilters;
+    use crate::service::repositories::model::{
+        AheadBehind, MergeBlockerKind, PipelineStatus, Repository,
+    };
+    use crate::test_support::serve_locally;
+
+    #[test]
+    fn encode_repository_with_subgroups() {
+        let repository = Repository::from_slug("group/subgroup/team/repo").unwrap();
+
+        assert_eq!(
+            "group%2Fsubgroup%2Fteam%2Frepo",
+            encode_repository_for_gitlab_url(&repository)
+        );
+    }
+
+    #[test]
+    fn map_canceling_and_unknown_pipeline_status() {
+        let map_status = |status: &str| {
+            let status: GitlabPipelineStatus = serde_json::from_value(json!(status)).unwrap();
+            map_gitlab_status(&status)
+        };
+
+        assert_eq!(PipelineStatus::Canceled, map_status("canceling"));
+        assert_eq!(PipelineStatus::None, map_status("some_future_status"));
+    }
+
+    fn get_merge_request(status: serde_json::Value) -> SingleMergeRequestResponse {
+        let mut merge_request = json!({
+            "iid": 1,
+            "title": "Feature",
+            "source_branch": "feature",
+            "target_branch": "main",
+            "author": { "username": "alice", "name": "Alice", "avatar_url": "image" },
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z",
+            "user_notes_count": 0,
+            "web_url": "https://gitlab.example.com/group/repo/-/merge_requests/1",
+        });
+        merge_request
+            .as_object_mut()
+            .unwrap()
+            .extend(status.as_object().unwrap().clone());
+        serde_json::from_value(merge_request).unwrap()
+    }
+
+    #[test]
+    fn map_detailed_merge_status() {
+        let mergeability = map_mergeability(&get_merge_request(json!({
+            "detailed_merge_status": "not_approved",
+            "has_conflicts": true,
+        })))
+        .unwrap();
+        let kinds: Vec<MergeBlockerKind> = mergeability
+            .blockers
+            .iter()
+            .map(|blocker| blocker.kind)
+            .collect();
+
+        assert!(!mergeability.can_merge);
+        assert_eq!(
+            vec![MergeBlockerKind::Approvals, MergeBlockerKind::Conflicts],
+            kinds
+        );
+
+        let mergeability = map_mergeability(&get_merge_request(json!({
+            "merge_status": "can_be_merged",
+        })))
+        .unwrap();
+        assert!(mergeability.can_merge);
+        assert!(mergeability.blockers.is_empty());
+
+        assert!(map_mergeability(&get_merge_request(json!({
+            "detailed_merge_status": "checking",
+        })))
+        .is_none());
+    }
+
+    fn get_client_and_project(url: String) -> (GitlabClient, ProjectDetails) {
+        let client = GitlabClient::new(
+            &[],
+            url,
+            "token".to_string(),
+            RepositoryDiscoveryConfiguration::default(),
+            BranchFilters::new(&BranchFiltersConfiguration::default()).unwrap(),
+        );
+        let project = ProjectDetails {
+            id: 1,
+            repository: Repository::from_slug("group/repo").unwrap(),
+            url: "https://gitlab.example.com/group/repo".to_string(),
+            default_branch: Some("main".to_string()),
+        };
+        (client, project)
+    }
+
+    #[tokio::test]
+    async fn get_ahead_behind_reuses_counts_of_same_commits() {
+        let merge_base_requests = Arc::new(AtomicUsize::new(0));
+        let counter = merge_base_requests.clone();
+        let url = serve_locally(|_| {
+            Router::new()
+                .route(
+                    "/api/v4/projects/1/repository/merge_base",
+                    get(|Query(query): Query<Vec<(String, String)>>| async move {
+                        counter.fetch_add(1, Ordering::SeqCst);
+                        let refs: Vec<&str> = query.iter().map(|(_, id)| id.as_str()).collect();
+                        match refs[..] {
+                            ["feature1", "main1"] => Ok(Json(json!({ "id": "base1" }))),
+                            _ => Err(StatusCode::NOT_FOUND),
+                        }
+                    }),
+                )
+                .route(
+                    "/api/v4/projects/1/repository/commits",
+                    get(|Query(query): Query<HashMap<String, String>>| async move {
+                        let total = match query["ref_name"].as_str() {
+                            "base1..feature1" => "2",
+                            "base1..main1" => "1",
+                            _ => return Err(StatusCode::NOT_FOUND),
+                        };
+                        Ok(([("X-Total", total)], Json(json!([{}]))))
+                    }),
+                )
+        })
+        .await;
+        let (client, project) = get_client_and_project(url);
+        let expected = Some(AheadBehind {
+            ahead: 2,
+            behind: 1,
+        });
+
+        assert_eq!(
+            expected,
+            get_ahead_behind(&client, &project, "feature1", "main1").await
+        );
+        client.ahead_behind_cache.lock().unwrap().finish_refresh();
+        assert_eq!(
+            expected,
+            get_ahead_behind(&client, &project, "feature1", "main1").await
+        );
+        assert_eq!(1, merge_base_requests.load(Ordering::SeqCst));
+
+        assert_eq!(
+            None,
+            get_ahead_behind(&client, &project, "unknown", "main1").await
+        );
+    }
+
+    #[tokio::test]
+    async fn get_pipeline_timing_loads_finished_pipelines_once() {
+        let pipeline_requests = Arc::new(AtomicUsize::new(0));
+        let counter = pipeline_requests.clone();
+        let url = serve_locally(|_| {
+            Router::new().route(
+                "/api/v4/projects/1/pipelines/7",
+                get(|| async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Json(json!({
+                        "id": 7,
+                        "status": "success",
+                        "sha": "abc",
+                        "updated_at": "2024-01-01T00:05:00Z",
+                        "duration": 300
+                    }))
+                }),
+            )
+        })
+        .await;
+        let (client, project) = get_client_and_project(url);
+        let get_listed_pipeline = |status: &str| -> PipelineResponse {
+            serde_json::from_value(json!({
+                "id": 7,
+                "status": status,
+                "sha": "abc",
+                "updated_at": "2024-01-01T00:05:00Z"
+            }))
+            .unwrap()
+        };
+
+        let running = get_pipeline_timing(&client, &project, get_listed_pipeline("running"))
+            .await
+            .unwrap();
+        assert_eq!(None, running.duration);
+        for _ in 0..2 {
+            let finished = get_pipeline_timing(&client, &project, get_listed_pipeline("success"))
+                .await
+                .unwrap();
+            assert_eq!(Some(300), finished.duration);
+        }
+        assert_eq!(1, pipeline_requests.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn get_branches_skips_hidden_branches() {
+        let requested_branches = Arc::new(Mutex::new(Vec::new()));
+        let recorder = requested_branches.clone();
+        let url = serve_locally(|_| {
+            Router::new().route(
+                "/api/v4/projects/1/pipelines",
+                get(|Query(query): Query<HashMap<String, String>>| async move {
+                    recorder.lock().unwrap().push(query["ref"].clone());
+                    Json(json!([]))
+                }),
+            )
+        })
+        .await;
+        let (mut client, project) = get_client_and_project(url);
+        client.branch_filters = BranchFilters::new(&BranchFiltersConfiguration {
+            default: BranchFilterConfiguration {
+                include: vec![],
+                exclude: vec!["wip-*".to_string()],
+                max_age_days: Some(30),
+            },
+            repositories: vec![],
+        })
+        .unwrap();
+        let get_branch = |name: &str, days_ago: i64| -> BranchResponse {
+            serde_json::from_value(json!({
+                "name": name,
+                "commit": {
+                    "id": name,
+                    "title": "Commit",
+                    "author_name": "Alice",
+                    "committed_date": (Utc::now() - Duration::days(days_ago)).format("%+").to_string()
+                }
+            }))
+            .unwrap()
+        };
+        let branches = vec![
+            get_branch("main", 1),
+            get_branch("wip-1", 1),
+            get_branch("feature-1", 60),
+        ];
+
+        let branch_details = get_branches(&client, &project, branches, &[])
+            .await
+            .unwrap();
+
+        assert_eq!(1, branch_details.len());
+        assert_eq!(vec!["main"], *requested_branches.lock().unwrap());
+    }
+}
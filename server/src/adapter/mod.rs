@@ -1,3 +1,7 @@
 pub mod bitbucket;
+pub mod chat;
 pub mod db;
 pub mod gitlab;
+pub mod oidc;
+pub mod outbound_webhook;
+pub mod smtp;
@@ -0,0 +1,132 @@
+use std::net::IpAddr;
+
+use anyhow::{anyhow, Context};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+use crate::adapter::oidc::model::{IdTokenClaims, ProviderMetadata, TokenResponse};
+
+pub mod model;
+
+/// Talks to an OpenID Connect provider for the authorization code flow.
+#[derive(Clone)]
+pub struct OidcClient {
+    client: reqwest::Client,
+    issuer_url: String,
+}
+
+impl OidcClient {
+    pub fn new(issuer_url: &str) -> Self {
+        OidcClient {
+            client: reqwest::Client::new(),
+            issuer_url: issuer_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub async fn discover(&self) -> anyhow::Result<ProviderMetadata> {
+        require_tls(&self.issuer_url)?;
+        let url = format!("{}/.well-known/openid-configuration", self.issuer_url);
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .context("Could not make discovery request to OpenID provider.")?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Unsuccessful response from OpenID provider for url {}: {}",
+                url,
+                response.status()
+            ));
+        }
+        response
+            .json()
+            .await
+            .context("Could not parse OpenID provider metadata.")
+    }
+
+    /// Exchanges the authorization code (and the PKCE verifier it was requested with) for tokens.
+    pub async fn exchange_code(
+        &self,
+        metadata: &ProviderMetadata,
+        client_id: &str,
+        client_secret: Option<&str>,
+        redirect_url: &str,
+        code: &str,
+        code_verifier: &str,
+    ) -> anyhow::Result<TokenResponse> {
+        require_tls(&metadata.token_endpoint)?;
+        let mut request = self.client.post(&metadata.token_endpoint).form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_url),
+            ("client_id", client_id),
+            ("code_verifier", code_verifier),
+        ]);
+        if let Some(client_secret) = client_secret {
+            request = request.basic_auth(client_id, Some(client_secret));
+        }
+        let response = request
+            .send()
+            .await
+            .context("Could not make token request to OpenID provider.")?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "Unsuccessful token response from OpenID provider: {}",
+                response.status()
+            ));
+        }
+        response
+            .json()
+            .await
+            .context("Could not parse token response.")
+    }
+}
+
+/// Rejects provider URLs which are not protected by TLS. Plain HTTP is only allowed for loopback
+/// addresses, e.g. for a provider running next to the dashboard during development.
+fn require_tls(url: &str) -> anyhow::Result<()> {
+    let parsed_url =
+        reqwest::Url::parse(url).with_context(|| format!("Invalid OpenID provider URL {url}."))?;
+    let is_loopback = parsed_url.host_str().is_some_and(|host| {
+        host == "localhost"
+            || host
+                .trim_start_matches('[')
+                .trim_end_matches(']')
+                .parse::<IpAddr>()
+                .is_ok_and(|address| address.is_loopback())
+    });
+    if parsed_url.scheme() == "https" || (parsed_url.scheme() == "http" && is_loopback) {
+        Ok(())
+    } else {
+        Err(anyhow!("OpenID provider URL {} does not use HTTPS.", url))
+    }
+}
+
+/// Reads the claims of an ID token. The signature is not verified: the token is only accepted
+/// straight from the token endpoint, which has to use TLS (OIDC Core 3.1.3.7).
+pub fn decode_id_token_claims(id_token: &str) -> anyhow::Result<IdTokenClaims> {
+    let payload = id_token
+        .split('.')
+        .nth(1)
+        .context("ID token is not a JWT.")?;
+    let decoded = URL_SAFE_NO_PAD
+        .decode(payload.trim_end_matches('='))
+        .context("Could not decode ID token payload.")?;
+    serde_json::from_slice(&decoded).context("Could not parse ID token claims.")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adapter::oidc::require_tls;
+
+    #[test]
+    fn require_tls_allows_plain_http_only_for_loopback() {
+        assert!(require_tls("https://idp.example.com/realms/dev").is_ok());
+        assert!(require_tls("http://127.0.0.1:8080").is_ok());
+        assert!(require_tls("http://[::1]:8080").is_ok());
+        assert!(require_tls("http://localhost/token").is_ok());
+        assert!(require_tls("http://idp.example.com").is_err());
+        assert!(require_tls("http://localhost.example.com").is_err());
+    }
+}
@@ -0,0 +1,59 @@
+use anyhow::Context;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+
+pub const EVENT_HEADER: &str = "X-Dashboard-Event";
+pub const DELIVERY_HEADER: &str = "X-Dashboard-Delivery";
+pub const SIGNATURE_HEADER: &str = "X-Dashboard-Signature-256";
+
+/// Posts JSON payloads to arbitrary HTTP endpoints, optionally signed with a shared secret.
+#[derive(Clone)]
+pub struct OutboundWebhookClient {
+    client: reqwest::Client,
+}
+
+impl OutboundWebhookClient {
+    pub fn new() -> Self {
+        OutboundWebhookClient {
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Posts the body and returns the response status code, regardless of whether it indicates
+    /// success.
+    pub async fn post(
+        &self,
+        url: &str,
+        secret: Option<&str>,
+        event_type: &str,
+        delivery_id: i32,
+        body: &str,
+    ) -> anyhow::Result<u16> {
+        let mut request = self
+            .client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, event_type)
+            .header(DELIVERY_HEADER, delivery_id.to_string());
+        if let Some(secret) = secret {
+            request = request.header(SIGNATURE_HEADER, sign_payload(secret, body)?);
+        }
+        let response = request
+            .body(body.to_string())
+            .send()
+            .await
+            .context("Could not make request to webhook.")?;
+        Ok(response.status().as_u16())
+    }
+}
+
+/// Computes the signature header value `sha256=<hex encoded HMAC-SHA256 of the body>`.
+pub fn sign_payload(secret: &str, body: &str) -> anyhow::Result<String> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .context("Could not create HMAC from webhook secret.")?;
+    mac.update(body.as_bytes());
+    Ok(format!(
+        "sha256={}",
+        hex::encode(mac.finalize().into_bytes())
+    ))
+}
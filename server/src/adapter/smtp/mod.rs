@@ -0,0 +1,69 @@
+use anyhow::Context;
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+use crate::config::{SmtpConfiguration, SmtpTlsMode};
+
+/// Sends plain text emails through the configured SMTP server.
+#[derive(Clone)]
+pub struct SmtpMailer {
+    transport: AsyncSmtpTransport<Tokio1Executor>,
+    from: Mailbox,
+}
+
+impl SmtpMailer {
+    pub fn new(configuration: &SmtpConfiguration) -> anyhow::Result<SmtpMailer> {
+        let host = configuration.host.as_str();
+        let mut builder = match configuration.tls {
+            SmtpTlsMode::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+            SmtpTlsMode::StartTls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+                .context("Could not configure STARTTLS SMTP transport.")?,
+            SmtpTlsMode::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+                .context("Could not configure TLS SMTP transport.")?,
+        };
+        if let Some(port) = configuration.port {
+            builder = builder.port(port);
+        }
+        if let (Some(user), Some(password)) = (&configuration.user, &configuration.password) {
+            builder = builder.credentials(Credentials::new(user.clone(), password.clone()));
+        }
+        let from = configuration
+            .from
+            .parse::<Mailbox>()
+            .with_context(|| format!("Invalid sender address {}.", configuration.from))?;
+
+        Ok(SmtpMailer {
+            transport: builder.build(),
+            from,
+        })
+    }
+
+    pub async fn send(
+        &self,
+        recipients: &[String],
+        subject: &str,
+        body: String,
+    ) -> anyhow::Result<()> {
+        let mut message_builder = Message::builder()
+            .from(self.from.clone())
+            .subject(subject)
+            .header(ContentType::TEXT_PLAIN);
+        for recipient in recipients {
+            let mailbox = recipient
+                .parse::<Mailbox>()
+                .with_context(|| format!("Invalid recipient address {recipient}."))?;
+            message_builder = message_builder.to(mailbox);
+        }
+        let message = message_builder
+            .body(body)
+            .context("Could not build email message.")?;
+
+        self.transport
+            .send(message)
+            .await
+            .context("Could not send email via SMTP.")?;
+        Ok(())
+    }
+}
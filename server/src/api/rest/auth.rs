@@ -0,0 +1,218 @@
+use std::sync::Arc;
+
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Redirect, Response};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use subtle::ConstantTimeEq;
+
+use crate::config::{AuthConfiguration, BasicAuthUser};
+use crate::service::auth::login_service::OidcLoginService;
+use crate::service::auth::model::AuthenticatedUser;
+
+const BASIC_AUTH_CHALLENGE: &str = "Basic realm=\"Git Server Dashboard\", charset=\"UTF-8\"";
+const BEARER_AUTH_CHALLENGE: &str = "Bearer";
+pub const SESSION_COOKIE: &str = "dashboard_session";
+
+/// State of the authentication middleware and the login routes.
+#[derive(Clone)]
+pub struct Authenticator {
+    configuration: Arc<AuthConfiguration>,
+    pub login_service: Option<OidcLoginService>,
+}
+
+impl Authenticator {
+    pub fn new(configuration: AuthConfiguration) -> Self {
+        let login_service = match &configuration {
+            AuthConfiguration::Oidc(oidc_configuration) => {
+                Some(OidcLoginService::new(oidc_configuration.clone()))
+            }
+            _ => None,
+        };
+        Self {
+            configuration: Arc::new(configuration),
+            login_service,
+        }
+    }
+}
+
+/// Middleware rejecting requests which do not carry credentials matching the configured mode.
+/// The user of the request is added as an `AuthenticatedUser` extension if the mode identifies
+/// users.
+pub async fn require_authentication(
+    State(authenticator): State<Authenticator>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let challenge = match authenticator.configuration.as_ref() {
+        AuthConfiguration::Oidc(_) => {
+            let Some(login_service) = &authenticator.login_service else {
+                return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+            };
+            if let Some(session_id) = get_cookie(request.headers(), SESSION_COOKIE) {
+                if let Some(user) = login_service.get_user(session_id).await {
+                    request.extensions_mut().insert(user);
+                    return next.run(request).await;
+                }
+            }
+            // API clients get an error, browsers are sent to the login of the provider
+            if !request.uri().path().starts_with("/api/") {
+                let return_to = request
+                    .uri()
+                    .path_and_query()
+                    .map(|path| path.as_str())
+                    .unwrap_or("/");
+                let query =
+                    serde_urlencoded::to_string([("return_to", return_to)]).unwrap_or_default();
+                return Redirect::to(&format!("/auth/login?{query}")).into_response();
+            }
+            return (StatusCode::UNAUTHORIZED, "Authentication required.").into_response();
+        }
+        AuthConfiguration::Basic { users } => {
+            if let Some(user) = get_basic_auth_user(users, request.headers()) {
+                request.extensions_mut().insert(user);
+            }
+            BASIC_AUTH_CHALLENGE
+        }
+        _ => BEARER_AUTH_CHALLENGE,
+    };
+    if is_authorized(&authenticator.configuration, request.headers()) {
+        return next.run(request).await;
+    }
+
+    (
+        StatusCode::UNAUTHORIZED,
+        [(
+            header::WWW_AUTHENTICATE,
+            HeaderValue::from_static(challenge),
+        )],
+        "Authentication required.",
+    )
+        .into_response()
+}
+
+/// Checks the credentials of the modes which authenticate every request on its own, OIDC sessions
+/// are checked by the middleware.
+fn is_authorized(auth_configuration: &AuthConfiguration, headers: &HeaderMap) -> bool {
+    match auth_configuration {
+        AuthConfiguration::None => true,
+        AuthConfiguration::ApiTokens { tokens } => has_valid_token(tokens, headers),
+        AuthConfiguration::Basic { users } => get_basic_auth_user(users, headers).is_some(),
+        AuthConfiguration::Oidc(_) => false,
+    }
+}
+
+fn has_valid_token(tokens: &[String], headers: &HeaderMap) -> bool {
+    get_credentials(headers, "Bearer").is_some_and(|token| {
+        tokens
+            .iter()
+            .any(|configured| secrets_match(configured, token))
+    })
+}
+
+fn get_basic_auth_user(users: &[BasicAuthUser], headers: &HeaderMap) -> Option<AuthenticatedUser> {
+    let decoded = STANDARD.decode(get_credentials(headers, "Basic")?).ok()?;
+    let credentials = String::from_utf8(decoded).ok()?;
+    let (user, password) = credentials.split_once(':')?;
+    users
+        .iter()
+        .find(|configured| configured.user == user && secrets_match(&configured.password, password))
+        .map(|configured| AuthenticatedUser {
+            id: configured.user.clone(),
+            username: configured.user.clone(),
+            name: configured.user.clone(),
+            email: None,
+        })
+}
+
+/// Compares in constant time to not reveal through the response time how much of a secret matched.
+fn secrets_match(configured: &str, given: &str) -> bool {
+    configured.as_bytes().ct_eq(given.as_bytes()).into()
+}
+
+/// Returns the credentials of the `Authorization` header if it uses the given scheme.
+fn get_credentials<'a>(headers: &'a HeaderMap, scheme: &str) -> Option<&'a str> {
+    let value = headers.get(header::AUTHORIZATION)?.to_str().ok()?;
+    let (request_scheme, credentials) = value.split_once(' ')?;
+    request_scheme
+        .eq_ignore_ascii_case(scheme)
+        .then(|| credentials.trim())
+}
+
+pub fn get_cookie<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .find(|(cookie_name, _)| *cookie_name == name)
+        .map(|(_, value)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{header, HeaderMap, HeaderValue};
+
+    use crate::api::rest::auth::{get_cookie, is_authorized};
+    use crate::config::{AuthConfiguration, BasicAuthUser};
+
+    fn get_headers(authorization: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(authorization).unwrap(),
+        );
+        headers
+    }
+
+    #[test]
+    fn is_authorized_api_tokens() {
+        let configuration = AuthConfiguration::ApiTokens {
+            tokens: vec!["token1".to_string(), "token2".to_string()],
+        };
+
+        assert!(is_authorized(&configuration, &get_headers("Bearer token2")));
+        assert!(!is_authorized(
+            &configuration,
+            &get_headers("Bearer token3")
+        ));
+        assert!(!is_authorized(&configuration, &get_headers("token1")));
+        assert!(!is_authorized(&configuration, &HeaderMap::new()));
+    }
+
+    #[test]
+    fn is_authorized_basic() {
+        let configuration = AuthConfiguration::Basic {
+            users: vec![BasicAuthUser {
+                user: "alice".to_string(),
+                password: "pass:word".to_string(),
+            }],
+        };
+
+        // base64 of "alice:pass:word" and "alice:wrong"
+        assert!(is_authorized(
+            &configuration,
+            &get_headers("Basic YWxpY2U6cGFzczp3b3Jk")
+        ));
+        assert!(!is_authorized(
+            &configuration,
+            &get_headers("Basic YWxpY2U6d3Jvbmc=")
+        ));
+        assert!(!is_authorized(&configuration, &get_headers("Bearer token")));
+    }
+
+    #[test]
+    fn get_cookie_finds_cookie_by_name() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::COOKIE,
+            HeaderValue::from_static("theme=dark; dashboard_session=abc"),
+        );
+
+        assert_eq!(Some("abc"), get_cookie(&headers, "dashboard_session"));
+        assert_eq!(None, get_cookie(&headers, "session"));
+    }
+}
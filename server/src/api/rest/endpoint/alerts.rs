@@ -0,0 +1,27 @@
+use anyhow::Context;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use log::error;
+use serde_json::json;
+
+use crate::api::rest::AppServicesState;
+
+pub async fn get_alerts(State(state): State<AppServicesState>) -> impl IntoResponse {
+    match state
+        .alert_service
+        .get_alerts()
+        .await
+        .context("Could not load alerts from DB.")
+    {
+        Ok(alerts) => (StatusCode::OK, Json(json!(alerts))),
+        Err(err) => {
+            error!("{:#}", err);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!("Could not load alerts.")),
+            )
+        }
+    }
+}
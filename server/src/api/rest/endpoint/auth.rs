@@ -0,0 +1,138 @@
+use axum::extract::{Query, State};
+use axum::http::{header, HeaderMap, StatusCode, Uri};
+use axum::response::{IntoResponse, Redirect, Response};
+use axum::{Extension, Json};
+use log::{error, warn};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::api::rest::auth::{get_cookie, Authenticator, SESSION_COOKIE};
+use crate::service::auth::login_service::OidcLoginService;
+use crate::service::auth::model::AuthenticatedUser;
+
+pub async fn get_current_user(user: Option<Extension<AuthenticatedUser>>) -> impl IntoResponse {
+    Json(json!(user.map(|Extension(user)| user)))
+}
+
+pub async fn login(
+    State(authenticator): State<Authenticator>,
+    Query(query): Query<LoginQuery>,
+) -> Response {
+    let Some(login_service) = authenticator.login_service else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let return_to = query
+        .return_to
+        .filter(|path| is_dashboard_path(path))
+        .unwrap_or_else(|| "/".to_string());
+    match login_service.start_login(&return_to).await {
+        Ok(authorization_url) => Redirect::to(&authorization_url).into_response(),
+        Err(err) => {
+            error!("Could not start login: {:#}", err);
+            (
+                StatusCode::BAD_GATEWAY,
+                "Could not reach the identity provider.",
+            )
+                .into_response()
+        }
+    }
+}
+
+pub async fn login_callback(
+    State(authenticator): State<Authenticator>,
+    Query(query): Query<CallbackQuery>,
+) -> Response {
+    let Some(login_service) = authenticator.login_service else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let (Some(code), Some(state)) = (query.code, query.state) else {
+        warn!(
+            "Login was rejected by the identity provider: {}",
+            query.error.unwrap_or_default()
+        );
+        return (StatusCode::UNAUTHORIZED, "Login failed.").into_response();
+    };
+    match login_service.finish_login(&code, &state).await {
+        Ok(login) => (
+            [(
+                header::SET_COOKIE,
+                get_session_cookie(&login_service, &login.session_id),
+            )],
+            Redirect::to(&login.return_to),
+        )
+            .into_response(),
+        Err(err) => {
+            error!("Could not complete login: {:#}", err);
+            (StatusCode::UNAUTHORIZED, "Login failed.").into_response()
+        }
+    }
+}
+
+pub async fn logout(State(authenticator): State<Authenticator>, headers: HeaderMap) -> Response {
+    let Some(login_service) = authenticator.login_service else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    if let Some(session_id) = get_cookie(&headers, SESSION_COOKIE) {
+        login_service.logout(session_id).await;
+    }
+    (
+        [(
+            header::SET_COOKIE,
+            format!("{SESSION_COOKIE}=; Path=/; HttpOnly; SameSite=Lax; Max-Age=0"),
+        )],
+        "Logged out.",
+    )
+        .into_response()
+}
+
+/// Whether the path stays on the dashboard itself, so that redirecting to it after the login
+/// cannot lead to another site. Browsers read backslashes as slashes, `/\host` is `//host` to them.
+fn is_dashboard_path(path: &str) -> bool {
+    path.starts_with('/')
+        && !path.starts_with("//")
+        && !path.contains('\\')
+        && !path.chars().any(char::is_control)
+        && path
+            .parse::<Uri>()
+            .is_ok_and(|uri| uri.authority().is_none())
+}
+
+fn get_session_cookie(login_service: &OidcLoginService, session_id: &str) -> String {
+    let secure = if login_service.is_secure() {
+        "; Secure"
+    } else {
+        ""
+    };
+    format!(
+        "{SESSION_COOKIE}={session_id}; Path=/; HttpOnly; SameSite=Lax; Max-Age={}{secure}",
+        login_service.get_session_duration().num_seconds()
+    )
+}
+
+#[derive(Deserialize)]
+pub struct LoginQuery {
+    pub return_to: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct CallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::api::rest::endpoint::auth::is_dashboard_path;
+
+    #[test]
+    fn is_dashboard_path_rejects_other_sites() {
+        assert!(is_dashboard_path("/"));
+        assert!(is_dashboard_path("/pipelines?branch=main"));
+        assert!(!is_dashboard_path("https://evil.com"));
+        assert!(!is_dashboard_path("//evil.com"));
+        assert!(!is_dashboard_path("/\\evil.com"));
+        assert!(!is_dashboard_path("/\t/evil.com"));
+        assert!(!is_dashboard_path("pipelines"));
+    }
+}
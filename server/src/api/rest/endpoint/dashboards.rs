@@ -0,0 +1,51 @@
+use axum::extract::{Extension, Path, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use serde_json::json;
+use tokio::sync::mpsc::UnboundedSender;
+
+use crate::api::rest::endpoint::get_data_and_trigger_reload;
+use crate::api::rest::endpoint::prupdates::{
+    respond_with_pr_updates, GetPullRequestUpdatesPayload,
+};
+use crate::api::rest::AppServicesState;
+use crate::service::dashboards::dashboard_service::filter_dashboard_data;
+use crate::LockableCache;
+
+pub async fn get_dashboards(State(state): State<AppServicesState>) -> impl IntoResponse {
+    Json(state.dashboard_service.get_dashboard_names())
+}
+
+pub async fn get_named_dashboard_data(
+    State(state): State<AppServicesState>,
+    Path(name): Path<String>,
+    Extension(cache): Extension<LockableCache>,
+    Extension(reload_sender): Extension<UnboundedSender<()>>,
+) -> impl IntoResponse {
+    let Some(dashboard) = state.dashboard_service.get_dashboard(&name) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!(format!("Unknown dashboard {name}."))),
+        );
+    };
+    let data = get_data_and_trigger_reload(&cache, &reload_sender).await;
+    (
+        StatusCode::OK,
+        Json(json!(filter_dashboard_data(dashboard, data))),
+    )
+}
+
+pub async fn get_named_dashboard_pr_updates(
+    State(state): State<AppServicesState>,
+    Path(name): Path<String>,
+    Json(payload): Json<GetPullRequestUpdatesPayload>,
+) -> impl IntoResponse {
+    let Some(dashboard) = state.dashboard_service.get_dashboard(&name) else {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!(format!("Unknown dashboard {name}."))),
+        );
+    };
+    respond_with_pr_updates(&state, payload, Some(dashboard)).await
+}
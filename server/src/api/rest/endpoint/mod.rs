@@ -4,9 +4,16 @@ use axum::Json;
 use log::warn;
 use tokio::sync::mpsc::UnboundedSender;
 
+use crate::service::repositories::model::RepositoriesData;
 use crate::{LockableCache, DASHBOARD_VERSION};
 
+pub mod alerts;
+pub mod auth;
+pub mod dashboards;
+pub mod pipelines;
 pub mod prupdates;
+pub mod pullrequests;
+pub mod webhooks;
 
 pub async fn get_server_version() -> impl IntoResponse {
     DASHBOARD_VERSION
@@ -14,12 +21,19 @@ pub async fn get_server_version() -> impl IntoResponse {
 
 pub async fn get_dashboard_data(
     Extension(cache): Extension<LockableCache>,
-    reload_sender: Extension<UnboundedSender<()>>,
+    Extension(reload_sender): Extension<UnboundedSender<()>>,
 ) -> impl IntoResponse {
-    let mut locked_cache = cache.lock().await;
-    let data = locked_cache.get_cached_data();
+    Json(get_data_and_trigger_reload(&cache, &reload_sender).await)
+}
+
+/// Returns the cached data and requests a reload, which only happens if the data is outdated.
+async fn get_data_and_trigger_reload(
+    cache: &LockableCache,
+    reload_sender: &UnboundedSender<()>,
+) -> RepositoriesData {
+    let data = cache.lock().await.get_cached_data();
     if let Err(err) = reload_sender.send(()) {
         warn!("Could not send reload event: {}.", err);
     }
-    Json(data)
+    data
 }
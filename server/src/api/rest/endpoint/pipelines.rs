@@ -0,0 +1,79 @@
+use anyhow::Context;
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use log::error;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::api::rest::AppServicesState;
+
+pub async fn get_pipeline_history(
+    State(state): State<AppServicesState>,
+    Query(query): Query<PipelineHistoryQuery>,
+) -> impl IntoResponse {
+    match state
+        .pipeline_history_service
+        .get_history(&query.repository, query.branch.as_deref())
+        .await
+        .context("Could not load pipeline history from DB.")
+    {
+        Ok(history) => (StatusCode::OK, Json(json!(history))),
+        Err(err) => {
+            error!("{:#}", err);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!("Could not load pipeline history.")),
+            )
+        }
+    }
+}
+
+pub async fn get_pipeline_statistics(
+    State(state): State<AppServicesState>,
+    Query(query): Query<PipelineHistoryQuery>,
+) -> impl IntoResponse {
+    match state
+        .pipeline_history_service
+        .get_statistics(&query.repository, query.branch.as_deref())
+        .await
+        .context("Could not load pipeline statistics from DB.")
+    {
+        Ok(statistics) => (StatusCode::OK, Json(json!(statistics))),
+        Err(err) => {
+            error!("{:#}", err);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!("Could not load pipeline statistics.")),
+            )
+        }
+    }
+}
+
+pub async fn get_pipeline_flakiness(
+    State(state): State<AppServicesState>,
+    Query(query): Query<PipelineHistoryQuery>,
+) -> impl IntoResponse {
+    match state
+        .pipeline_history_service
+        .get_flakiness(&query.repository, query.branch.as_deref())
+        .await
+        .context("Could not load pipeline runs from DB.")
+    {
+        Ok(flakiness) => (StatusCode::OK, Json(json!(flakiness))),
+        Err(err) => {
+            error!("{:#}", err);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!("Could not load pipeline flakiness.")),
+            )
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct PipelineHistoryQuery {
+    pub repository: String,
+    pub branch: Option<String>,
+}
@@ -6,16 +6,28 @@ use axum::Json;
 use chrono::{DateTime, Utc};
 use log::error;
 use serde::Deserialize;
-use serde_json::json;
+use serde_json::{json, Value};
 use std::collections::HashMap;
 
 use crate::api::rest::AppServicesState;
+use crate::config::DashboardConfiguration;
+use crate::service::dashboards::dashboard_service::filter_dashboard_pr_updates;
 use crate::service::prupdates::model::PullRequestTimestamp;
 
 pub async fn get_pr_updates(
     State(state): State<AppServicesState>,
     Json(payload): Json<GetPullRequestUpdatesPayload>,
 ) -> impl IntoResponse {
+    respond_with_pr_updates(&state, payload, None).await
+}
+
+/// Loads the PR updates the client has not seen yet, only the ones in the PR update scope of the
+/// dashboard if one is given.
+pub async fn respond_with_pr_updates(
+    state: &AppServicesState,
+    payload: GetPullRequestUpdatesPayload,
+    dashboard: Option<&DashboardConfiguration>,
+) -> (StatusCode, Json<Value>) {
     let last_seen_timestamps = payload
         .pull_requests_last_seen
         .into_iter()
@@ -28,7 +40,13 @@ pub async fn get_pr_updates(
         .await
         .context("Could not load pull request events from DB.")
     {
-        Ok(events) => (StatusCode::OK, Json(json!(events))),
+        Ok(updates) => {
+            let updates = match dashboard {
+                Some(dashboard) => filter_dashboard_pr_updates(dashboard, updates),
+                None => updates,
+            };
+            (StatusCode::OK, Json(json!(updates)))
+        }
         Err(err) => {
             error!("{:#}", err);
             (
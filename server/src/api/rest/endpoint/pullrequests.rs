@@ -0,0 +1,38 @@
+use axum::extract::{Extension, Query};
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::service::auth::model::AuthenticatedUser;
+use crate::service::repositories::user_pull_requests::get_user_pull_requests;
+use crate::LockableCache;
+
+/// Lists the PRs of the requested user, or of the logged-in user if none is requested.
+pub async fn get_pull_requests_of_user(
+    Extension(cache): Extension<LockableCache>,
+    authenticated_user: Option<Extension<AuthenticatedUser>>,
+    Query(query): Query<UserPullRequestsQuery>,
+) -> impl IntoResponse {
+    let Some(user) = query
+        .user
+        .or(authenticated_user.map(|Extension(user)| user.username))
+    else {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(json!("No user given and not logged in.")),
+        );
+    };
+
+    let data = cache.lock().await.get_cached_data();
+    (
+        StatusCode::OK,
+        Json(json!(get_user_pull_requests(&data, &user))),
+    )
+}
+
+#[derive(Deserialize)]
+pub struct UserPullRequestsQuery {
+    pub user: Option<String>,
+}
@@ -0,0 +1,27 @@
+use anyhow::Context;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::Json;
+use log::error;
+use serde_json::json;
+
+use crate::api::rest::AppServicesState;
+
+pub async fn get_webhook_deliveries(State(state): State<AppServicesState>) -> impl IntoResponse {
+    match state
+        .webhook_service
+        .get_deliveries()
+        .await
+        .context("Could not load webhook deliveries from DB.")
+    {
+        Ok(deliveries) => (StatusCode::OK, Json(json!(deliveries))),
+        Err(err) => {
+            error!("{:#}", err);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(json!("Could not load webhook deliveries.")),
+            )
+        }
+    }
+}
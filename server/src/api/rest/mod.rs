@@ -1,9 +1,18 @@
+use crate::service::alerts::alert_service::AlertService;
+use crate::service::dashboards::dashboard_service::DashboardService;
+use crate::service::notifications::webhook_service::WebhookService;
+use crate::service::pipelines::pipeline_history_service::PipelineHistoryService;
 use crate::service::prupdates::pr_event_service::PullRequestUpdateService;
 
+mod auth;
 mod endpoint;
 pub mod routes;
 
 #[derive(Clone)]
 pub struct AppServicesState {
     pub pr_update_service: PullRequestUpdateService,
+    pub pipeline_history_service: PipelineHistoryService,
+    pub alert_service: AlertService,
+    pub webhook_service: WebhookService,
+    pub dashboard_service: DashboardService,
 }
@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use axum::error_handling::HandleErrorLayer;
 use axum::http::StatusCode;
 use axum::Router;
@@ -5,33 +7,87 @@ use log::error;
 use tokio::sync::mpsc::UnboundedSender;
 use tower::ServiceBuilder;
 
+use crate::api::rest::auth::{require_authentication, Authenticator};
+use crate::api::rest::endpoint::alerts::get_alerts;
+use crate::api::rest::endpoint::auth::{get_current_user, login, login_callback, logout};
+use crate::api::rest::endpoint::dashboards::{
+    get_dashboards, get_named_dashboard_data, get_named_dashboard_pr_updates,
+};
+use crate::api::rest::endpoint::pipelines::{
+    get_pipeline_flakiness, get_pipeline_history, get_pipeline_statistics,
+};
 use crate::api::rest::endpoint::prupdates::get_pr_updates;
+use crate::api::rest::endpoint::pullrequests::get_pull_requests_of_user;
+use crate::api::rest::endpoint::webhooks::get_webhook_deliveries;
 use crate::api::rest::endpoint::{get_dashboard_data, get_server_version};
 use crate::api::rest::AppServicesState;
-use crate::api::webhook::post_webhook_bitbucket;
-use crate::service::prupdates::pr_event_service::PullRequestUpdateService;
+use crate::api::webhook::{post_webhook_bitbucket, WebhookState};
+use crate::config::AuthConfiguration;
 use crate::LockableCache;
 
 pub fn get_router(
     cache: LockableCache,
-    pr_event_service: PullRequestUpdateService,
+    state: AppServicesState,
     reload_sender: UnboundedSender<()>,
+    static_folder: PathBuf,
+    auth_configuration: AuthConfiguration,
+    bitbucket_webhook_secret: Option<String>,
 ) -> anyhow::Result<Router> {
-    let state = AppServicesState {
-        pr_update_service: pr_event_service,
-    };
+    // webhooks are not authenticated by the dashboard, they rely on their own secrets
+    let webhook_router = Router::new()
+        .route(
+            "/webhook/bitbucket",
+            axum::routing::post(post_webhook_bitbucket),
+        )
+        .with_state(WebhookState {
+            pr_update_service: state.pr_update_service.clone(),
+            bitbucket_secret: bitbucket_webhook_secret,
+        });
+    let authenticator = Authenticator::new(auth_configuration);
+    let login_router = Router::new()
+        .route("/auth/login", axum::routing::get(login))
+        .route("/auth/callback", axum::routing::get(login_callback))
+        .route("/auth/logout", axum::routing::post(logout))
+        .with_state(authenticator.clone());
 
     let api_router = Router::new()
         .route("/api/version", axum::routing::get(get_server_version))
+        .route("/api/user", axum::routing::get(get_current_user))
         .route(
             "/api/dashboard-data",
             axum::routing::get(get_dashboard_data),
         )
+        .route("/api/pr-updates", axum::routing::post(get_pr_updates))
+        .route("/api/dashboards", axum::routing::get(get_dashboards))
         .route(
-            "/webhook/bitbucket",
-            axum::routing::post(post_webhook_bitbucket),
+            "/api/dashboards/:name/dashboard-data",
+            axum::routing::get(get_named_dashboard_data),
+        )
+        .route(
+            "/api/dashboards/:name/pr-updates",
+            axum::routing::post(get_named_dashboard_pr_updates),
+        )
+        .route(
+            "/api/pull-requests",
+            axum::routing::get(get_pull_requests_of_user),
+        )
+        .route("/api/alerts", axum::routing::get(get_alerts))
+        .route(
+            "/api/webhook-deliveries",
+            axum::routing::get(get_webhook_deliveries),
+        )
+        .route(
+            "/api/pipeline-history",
+            axum::routing::get(get_pipeline_history),
+        )
+        .route(
+            "/api/pipeline-statistics",
+            axum::routing::get(get_pipeline_statistics),
+        )
+        .route(
+            "/api/pipeline-flakiness",
+            axum::routing::get(get_pipeline_flakiness),
         )
-        .route("/api/pr-updates", axum::routing::post(get_pr_updates))
         .layer(axum::extract::Extension(cache))
         .layer(axum::extract::Extension(reload_sender))
         .with_state(state);
@@ -39,11 +95,18 @@ pub fn get_router(
     // routes (matched from bottom to top from more specific to less specific)
     let router = Router::new()
         .fallback_service(
-            axum::routing::get_service(tower_http::services::ServeDir::new("static")).handle_error(
-                |err| async move { error!("error occurred when serving static file: {}.", err) },
-            ),
+            axum::routing::get_service(tower_http::services::ServeDir::new(static_folder))
+                .handle_error(|err| async move {
+                    error!("error occurred when serving static file: {}.", err)
+                }),
         )
-        .merge(api_router);
+        .merge(api_router)
+        .layer(axum::middleware::from_fn_with_state(
+            authenticator,
+            require_authentication,
+        ))
+        .merge(webhook_router)
+        .merge(login_router);
 
     let middleware_stack = ServiceBuilder::new().layer(HandleErrorLayer::new(|error| async move {
         (
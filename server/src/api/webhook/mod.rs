@@ -2,14 +2,15 @@ use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 
 use anyhow::{anyhow, Context};
+use axum::body::Bytes;
 use axum::extract::State;
-use axum::http::StatusCode;
+use axum::http::{HeaderMap, StatusCode};
 use axum::response::IntoResponse;
-use axum::Json;
-use log::{error, info};
+use hmac::{Hmac, Mac};
+use log::{error, info, warn};
 use serde::de::DeserializeOwned;
+use sha2::Sha256;
 
-use crate::api::rest::AppServicesState;
 use crate::api::webhook::model::{
     CommonPullRequestEventPayload, PREventType, PullRequestCommentEventPayload, PullRequestPayload,
 };
@@ -18,11 +19,34 @@ use crate::service::prupdates::pr_event_service::PullRequestUpdateService;
 
 mod model;
 
+const BITBUCKET_SIGNATURE_HEADER: &str = "X-Hub-Signature";
+
+#[derive(Clone)]
+pub struct WebhookState {
+    pub pr_update_service: PullRequestUpdateService,
+    /// Secret Bitbucket signs the webhook requests with, requests are not verified without it.
+    pub bitbucket_secret: Option<String>,
+}
+
 #[axum_macros::debug_handler]
 pub async fn post_webhook_bitbucket(
-    State(state): State<AppServicesState>,
-    Json(payload): Json<serde_json::Value>,
+    State(state): State<WebhookState>,
+    headers: HeaderMap,
+    body: Bytes,
 ) -> impl IntoResponse {
+    if let Some(secret) = &state.bitbucket_secret {
+        if !has_valid_signature(secret, &headers, &body) {
+            warn!("Rejected Bitbucket webhook request without valid signature.");
+            return (StatusCode::UNAUTHORIZED, "Invalid signature.");
+        }
+    }
+    let payload = match serde_json::from_slice::<serde_json::Value>(&body) {
+        Ok(payload) => payload,
+        Err(err) => {
+            error!("Could not parse webhook from Bitbucket: {:#}", err);
+            return (StatusCode::BAD_REQUEST, "Could not parse request body.");
+        }
+    };
     match process_webhook_request(payload, &state.pr_update_service).await {
         Ok(()) => (StatusCode::OK, ""),
         Err(err) => {
@@ -32,6 +56,24 @@ pub async fn post_webhook_bitbucket(
     }
 }
 
+/// Checks the `sha256=<hex encoded HMAC-SHA256 of the body>` signature Bitbucket sends for
+/// webhooks with a secret. The comparison takes constant time.
+fn has_valid_signature(secret: &str, headers: &HeaderMap, body: &[u8]) -> bool {
+    let Some(signature) = headers
+        .get(BITBUCKET_SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("sha256="))
+        .and_then(|value| hex::decode(value).ok())
+    else {
+        return false;
+    };
+    let Ok(mut mac) = Hmac::<Sha256>::new_from_slice(secret.as_bytes()) else {
+        return false;
+    };
+    mac.update(body);
+    mac.verify_slice(&signature).is_ok()
+}
+
 async fn process_webhook_request(
     value: serde_json::Value,
     pr_update_service: &PullRequestUpdateService,
@@ -74,6 +116,9 @@ fn parse_pr_event_payload(
     let pr_link = get_pull_request_link(&payload.pull_request)?;
 
     let timestamp = chrono::offset::Utc::now();
+    // named like the repositories in the dashboard data, e.g. PROJECT/repo
+    let repository = &payload.pull_request.from_ref.repository;
+    let repository = format!("{}/{}", repository.project.key, repository.slug);
 
     // TODO also parse author of change if possible (who commented? who approved?)
 
@@ -83,7 +128,7 @@ fn parse_pr_event_payload(
         pr_id: pr_id.to_string(),
         author: payload.actor.display_name,
         timestamp,
-        repository: payload.pull_request.from_ref.repository.name,
+        repository,
         title: payload.pull_request.title,
         text,
         pr_link,
@@ -145,3 +190,46 @@ fn get_pull_request_link(pull_request: &PullRequestPayload) -> anyhow::Result<St
         .map(|link| link.href.clone())
         .ok_or_else(|| anyhow!("Could not find self link on Bitbucket webhook payload."))
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::http::{HeaderMap, HeaderValue};
+
+    use crate::adapter::outbound_webhook::sign_payload;
+    use crate::api::webhook::{has_valid_signature, BITBUCKET_SIGNATURE_HEADER};
+
+    #[test]
+    fn has_valid_signature_checks_hmac_of_body() {
+        let body = r#"{"test": true}"#;
+        let get_headers = |signature: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                BITBUCKET_SIGNATURE_HEADER,
+                HeaderValue::from_str(signature).unwrap(),
+            );
+            headers
+        };
+        let signature = sign_payload("secret", body).unwrap();
+
+        assert!(has_valid_signature(
+            "secret",
+            &get_headers(&signature),
+            body.as_bytes()
+        ));
+        assert!(!has_valid_signature(
+            "other",
+            &get_headers(&signature),
+            body.as_bytes()
+        ));
+        assert!(!has_valid_signature(
+            "secret",
+            &get_headers("sha256=abc"),
+            body.as_bytes()
+        ));
+        assert!(!has_valid_signature(
+            "secret",
+            &HeaderMap::new(),
+            body.as_bytes()
+        ));
+    }
+}
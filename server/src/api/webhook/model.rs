@@ -55,13 +55,14 @@ pub(super) struct GitRefPayload {
 #[derive(Deserialize)]
 pub(super) struct GitRepositoryPayload {
     pub id: u32,
-    pub name: String,
+    pub slug: String,
     pub project: GitProjectPayload,
 }
 
 #[derive(Deserialize)]
 pub(super) struct GitProjectPayload {
     pub id: u32,
+    pub key: String,
 }
 
 #[derive(Deserialize)]
@@ -1,13 +1,14 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use anyhow::Context;
+use anyhow::{anyhow, Context};
 use serde::Deserialize;
 
-#[derive(Clone, Debug, Deserialize)]
-pub enum VCSServerType {
-    Gitlab,
-    Bitbucket,
-}
+const DEFAULT_DATA_FOLDER: &str = "data";
+const DEFAULT_DB_FILE_NAME: &str = "data.sqlite";
+const DEFAULT_STATIC_FOLDER: &str = "static";
+const DEFAULT_MAX_MESSAGES_PER_MINUTE: usize = 20;
+const DEFAULT_OIDC_SCOPES: [&str; 3] = ["openid", "profile", "email"];
+const DEFAULT_SESSION_HOURS: i64 = 8;
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct GitlabConfiguration {
@@ -20,6 +21,267 @@ pub struct BitbucketConfiguration {
     pub url: String,
     pub user: String,
     pub password: String,
+    /// Secret of the Bitbucket webhook, requests to the webhook route must be signed with it.
+    pub webhook_secret: Option<String>,
+}
+
+/// Locations of the files the dashboard reads and writes. Relative paths are resolved against the
+/// working directory.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct PathsConfiguration {
+    /// Folder for state such as the database (default: `data`).
+    pub data: Option<PathBuf>,
+    /// Path of the sqlite database file (default: `data.sqlite` inside the data folder).
+    pub database: Option<PathBuf>,
+    /// Folder containing the static UI assets (default: `static`).
+    #[serde(rename = "static")]
+    pub static_files: Option<PathBuf>,
+}
+
+impl PathsConfiguration {
+    pub fn data_folder(&self) -> PathBuf {
+        self.data
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_DATA_FOLDER))
+    }
+
+    pub fn database_file(&self) -> PathBuf {
+        self.database
+            .clone()
+            .unwrap_or_else(|| self.data_folder().join(DEFAULT_DB_FILE_NAME))
+    }
+
+    pub fn static_folder(&self) -> PathBuf {
+        self.static_files
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_STATIC_FOLDER))
+    }
+}
+
+/// A condition that raises an alert on the dashboard while it holds.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type")]
+pub enum AlertRuleConfiguration {
+    /// The pipeline of a PR target branch has been failing for longer than the given minutes.
+    TargetBranchFailed { minutes: i64 },
+    /// A PR has been open for longer than the given days without being approved.
+    PullRequestAwaitingApproval { days: i64 },
+    /// A branch which is not part of a PR has no pipeline.
+    StandaloneBranchWithoutPipeline,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct AlertsConfiguration {
+    #[serde(default)]
+    pub rules: Vec<AlertRuleConfiguration>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub enum ChatKind {
+    Slack,
+    Mattermost,
+    Teams,
+}
+
+/// An incoming webhook of a chat tool that notifications are posted to.
+#[derive(Clone, Debug, Deserialize)]
+pub struct ChatNotificationConfiguration {
+    pub kind: ChatKind,
+    pub url: String,
+    /// Repositories to send notifications for, all repositories if empty.
+    #[serde(default)]
+    pub repositories: Vec<String>,
+    #[serde(default = "default_max_messages_per_minute")]
+    pub max_messages_per_minute: usize,
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+pub enum SmtpTlsMode {
+    /// Unencrypted connection, only meant for local relays.
+    None,
+    #[default]
+    StartTls,
+    Tls,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SmtpConfiguration {
+    pub host: String,
+    pub port: Option<u16>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    #[serde(default)]
+    pub tls: SmtpTlsMode,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(tag = "type")]
+pub enum DigestSchedule {
+    /// At the start of every hour.
+    Hourly,
+    /// Every day at the given hour (UTC).
+    Daily { hour: u32 },
+}
+
+/// An email digest of PR activity sent to a team.
+#[derive(Clone, Debug, Deserialize)]
+pub struct DigestConfiguration {
+    pub team: String,
+    pub recipients: Vec<String>,
+    pub schedule: DigestSchedule,
+    /// Repositories included in the digest, all repositories if empty.
+    #[serde(default)]
+    pub repositories: Vec<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub enum WebhookEventType {
+    PullRequest,
+    PipelineStatusChanged,
+}
+
+/// An HTTP endpoint that dashboard events are forwarded to as JSON.
+#[derive(Clone, Debug, Deserialize)]
+pub struct WebhookConfiguration {
+    pub url: String,
+    /// Used to sign the payload with HMAC-SHA256, sent in the `X-Dashboard-Signature-256` header.
+    pub secret: Option<String>,
+    /// Events to forward, all events if empty.
+    #[serde(default)]
+    pub events: Vec<WebhookEventType>,
+    /// Repositories to forward events for, all repositories if empty.
+    #[serde(default)]
+    pub repositories: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct NotificationsConfiguration {
+    #[serde(default)]
+    pub chat: Vec<ChatNotificationConfiguration>,
+    pub smtp: Option<SmtpConfiguration>,
+    #[serde(default)]
+    pub digests: Vec<DigestConfiguration>,
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfiguration>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct BasicAuthUser {
+    pub user: String,
+    pub password: String,
+}
+
+/// Login via the authorization code flow (with PKCE) of an OpenID Connect provider.
+#[derive(Clone, Debug, Deserialize)]
+pub struct OidcConfiguration {
+    /// Issuer URL the provider metadata is discovered from, has to use HTTPS unless it is local.
+    pub issuer_url: String,
+    pub client_id: String,
+    /// Secret of confidential clients, omitted for public clients.
+    pub client_secret: Option<String>,
+    /// Callback URL registered at the provider, i.e. `<dashboard url>/auth/callback`.
+    pub redirect_url: String,
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: Vec<String>,
+    #[serde(default = "default_session_hours")]
+    pub session_hours: i64,
+}
+
+/// How clients authenticate against the dashboard and its API. Webhook routes are always exempt
+/// as they are secured by their own secrets.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(tag = "mode")]
+pub enum AuthConfiguration {
+    /// Everything is public.
+    #[default]
+    None,
+    /// Requests need an `Authorization: Bearer <token>` header with one of the tokens.
+    ApiTokens { tokens: Vec<String> },
+    /// Requests need HTTP basic auth credentials of one of the users.
+    Basic { users: Vec<BasicAuthUser> },
+    /// Users log in at an OpenID Connect provider and are identified by a session cookie.
+    Oidc(OidcConfiguration),
+}
+
+fn default_max_messages_per_minute() -> usize {
+    DEFAULT_MAX_MESSAGES_PER_MINUTE
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_oidc_scopes() -> Vec<String> {
+    DEFAULT_OIDC_SCOPES
+        .iter()
+        .map(|scope| scope.to_string())
+        .collect()
+}
+
+fn default_session_hours() -> i64 {
+    DEFAULT_SESSION_HOURS
+}
+
+/// Options for repositories discovered from namespace entries like `PROJECT/*` in `repositories`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct RepositoryDiscoveryConfiguration {
+    #[serde(default = "default_true")]
+    pub exclude_archived: bool,
+    #[serde(default)]
+    pub exclude_forks: bool,
+}
+
+impl Default for RepositoryDiscoveryConfiguration {
+    fn default() -> Self {
+        Self {
+            exclude_archived: true,
+            exclude_forks: false,
+        }
+    }
+}
+
+/// Rules deciding which branches that are not part of a PR are shown. Patterns are globs where
+/// `*` matches any characters, or regular expressions when enclosed in slashes (`/^release-\d+$/`).
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct BranchFilterConfiguration {
+    /// Only branches matching one of these patterns are shown, all branches if empty.
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Branches matching one of these patterns are hidden, even if they are included.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Branches without commits in the given number of days are hidden.
+    pub max_age_days: Option<i64>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct RepositoryBranchFilterConfiguration {
+    pub repository: String,
+    pub filter: BranchFilterConfiguration,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct BranchFiltersConfiguration {
+    /// Applies to every repository without its own filter.
+    #[serde(default)]
+    pub default: BranchFilterConfiguration,
+    /// Filters replacing the default one for single repositories.
+    #[serde(default)]
+    pub repositories: Vec<RepositoryBranchFilterConfiguration>,
+}
+
+/// A named view on a subset of the loaded repositories, e.g. for the wall screen of one team.
+#[derive(Clone, Debug, Deserialize)]
+pub struct DashboardConfiguration {
+    pub name: String,
+    pub repositories: Vec<String>,
+    /// Branch names shown on the dashboard where `*` matches any characters, all if empty.
+    #[serde(default)]
+    pub branches: Vec<String>,
+    /// Repositories whose PR updates are shown, the repositories of the dashboard if empty.
+    #[serde(default)]
+    pub pr_update_repositories: Vec<String>,
 }
 
 #[derive(Clone, Debug, Deserialize)]
@@ -28,7 +290,39 @@ pub struct Configuration {
     pub port: u16,
     pub gitlab: Option<GitlabConfiguration>,
     pub bitbucket: Option<BitbucketConfiguration>,
+    #[serde(default)]
     pub repositories: Vec<String>,
+    #[serde(default)]
+    pub dashboards: Vec<DashboardConfiguration>,
+    #[serde(default)]
+    pub branch_filters: BranchFiltersConfiguration,
+    #[serde(default)]
+    pub repository_discovery: RepositoryDiscoveryConfiguration,
+    #[serde(default)]
+    pub paths: PathsConfiguration,
+    #[serde(default)]
+    pub alerts: AlertsConfiguration,
+    #[serde(default)]
+    pub notifications: NotificationsConfiguration,
+    #[serde(default)]
+    pub auth: AuthConfiguration,
+}
+
+impl Configuration {
+    /// All repositories to load, i.e. the top level ones and the ones of every dashboard.
+    pub fn all_repositories(&self) -> Vec<String> {
+        let mut repositories = self.repositories.clone();
+        for repository in self
+            .dashboards
+            .iter()
+            .flat_map(|dashboard| &dashboard.repositories)
+        {
+            if !repositories.contains(repository) {
+                repositories.push(repository.clone());
+            }
+        }
+        repositories
+    }
 }
 
 pub fn load_configuration() -> anyhow::Result<Configuration> {
@@ -52,6 +346,22 @@ pub fn load_configuration() -> anyhow::Result<Configuration> {
     let configuration: Configuration = config
         .try_deserialize()
         .context("Could not read configuration.")?;
+    validate_configuration(&configuration)?;
 
     Ok(configuration)
 }
+
+fn validate_configuration(configuration: &Configuration) -> anyhow::Result<()> {
+    for digest in &configuration.notifications.digests {
+        if let DigestSchedule::Daily { hour } = digest.schedule {
+            if hour > 23 {
+                return Err(anyhow!(
+                    "Invalid hour {} in the digest schedule of team {}, expected 0 to 23.",
+                    hour,
+                    digest.team
+                ));
+            }
+        }
+    }
+    Ok(())
+}
@@ -1,33 +1,49 @@
 extern crate core;
 
 use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
 use anyhow::{anyhow, Context};
-use log::{error, info, LevelFilter};
+use log::{error, info, warn, LevelFilter};
 use sea_orm::{ConnectOptions, Database, DatabaseConnection};
 use tokio::sync::mpsc::UnboundedSender;
 
 use migration::{Migrator, MigratorTrait};
 
+use crate::adapter::db::alerts::AlertRepository;
+use crate::adapter::db::pipelines::{PipelineRunRepository, PipelineStatusTransitionRepository};
 use crate::adapter::db::prupdates::PullRequestEventRepository;
+use crate::adapter::db::snapshots::RepositorySnapshotRepository;
+use crate::adapter::db::webhooks::WebhookDeliveryRepository;
+use crate::adapter::smtp::SmtpMailer;
 use crate::api::rest::routes::get_router;
-use crate::config::Configuration;
+use crate::api::rest::AppServicesState;
+use crate::config::{AuthConfiguration, Configuration};
+use crate::service::alerts::alert_service::AlertService;
+use crate::service::dashboards::dashboard_service::DashboardService;
+use crate::service::events::DashboardEventPublisher;
+use crate::service::notifications::chat_notifier::ChatNotifier;
+use crate::service::notifications::digest_notifier::DigestNotifier;
+use crate::service::notifications::webhook_service::WebhookService;
+use crate::service::pipelines::pipeline_history_service::PipelineHistoryService;
 use crate::service::prupdates::pr_event_service::PullRequestUpdateService;
 use crate::service::repositories::cache::RepositoriesDataCache;
 use crate::service::repositories::loader::DataLoader;
-use crate::service::repositories::{keep_loading_repositories_data, LockableCache};
+use crate::service::repositories::{
+    keep_loading_repositories_data, LoadedDataHandlers, LockableCache,
+};
 
 mod adapter;
 mod api;
 mod config;
 mod logger;
 mod service;
+#[cfg(test)]
+mod test_support;
 
 const DASHBOARD_VERSION: &str = env!("CARGO_PKG_VERSION");
-const DATA_FOLDER: &str = "data";
-const DB_FILE_NAME: &str = "data.sqlite";
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -35,34 +51,112 @@ async fn main() -> anyhow::Result<()> {
         .context("Could not load configuration from file or environment.")?;
     logger::init_logger(configuration.verbose);
 
-    let db_connection = setup_db_connection()
+    let db_connection = setup_db_connection(&configuration.paths.database_file())
         .await
         .context("Could not setup db connection.")?;
     // migrate database schema
     Migrator::up(&db_connection, None).await.unwrap();
 
-    let cache = Arc::new(tokio::sync::Mutex::new(RepositoriesDataCache::new()));
+    let event_publisher = DashboardEventPublisher::new();
+    if !configuration.notifications.chat.is_empty() {
+        ChatNotifier::new(configuration.notifications.chat.clone())
+            .start(event_publisher.subscribe());
+    }
+
+    let snapshot_repository = RepositorySnapshotRepository::new(db_connection.clone());
+    let pipeline_history_service = PipelineHistoryService::new(
+        PipelineStatusTransitionRepository::new(db_connection.clone()),
+        PipelineRunRepository::new(db_connection.clone()),
+    );
+    let mut repositories_data_cache = RepositoriesDataCache::new();
+    match snapshot_repository.load_snapshot().await {
+        Ok(Some(snapshot)) => {
+            info!("Serving persisted dashboard data until the first load has finished.");
+            repositories_data_cache.restore_data(snapshot);
+        }
+        Ok(None) => {}
+        Err(err) => warn!("Could not load persisted dashboard data: {:#}", err),
+    }
+    let alert_service = AlertService::new(
+        AlertRepository::new(db_connection.clone()),
+        pipeline_history_service.clone(),
+        configuration.alerts.rules.clone(),
+    );
+    let cache = Arc::new(tokio::sync::Mutex::new(repositories_data_cache));
     let data_loader = DataLoader::new(&configuration)?;
     let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
     tokio::spawn(keep_loading_repositories_data(
         rx,
         cache.clone(),
         data_loader,
+        LoadedDataHandlers {
+            snapshot_repository,
+            pipeline_history_service: pipeline_history_service.clone(),
+            alert_service: alert_service.clone(),
+            event_publisher: event_publisher.clone(),
+        },
     ));
+    // start the first load right away instead of waiting for the first request
+    tx.send(())
+        .context("Could not trigger initial data load.")?;
+
+    let webhook_service = WebhookService::new(
+        configuration.notifications.webhooks.clone(),
+        WebhookDeliveryRepository::new(db_connection.clone()),
+    );
+    if !configuration.notifications.webhooks.is_empty() {
+        webhook_service.start(event_publisher.subscribe());
+    }
 
-    start_with_config(configuration.port, cache, db_connection, tx).await?;
+    let pr_event_repository = PullRequestEventRepository::new(db_connection);
+    let state = AppServicesState {
+        pr_update_service: PullRequestUpdateService::new(pr_event_repository, event_publisher),
+        pipeline_history_service,
+        alert_service,
+        webhook_service,
+        dashboard_service: DashboardService::new(configuration.dashboards.clone()),
+    };
+
+    if !configuration.notifications.digests.is_empty() {
+        let smtp_configuration = configuration
+            .notifications
+            .smtp
+            .as_ref()
+            .context("Invalid configuration: digests require an SMTP server.")?;
+        let mailer = SmtpMailer::new(smtp_configuration).context("Could not create mailer.")?;
+        DigestNotifier::new(
+            configuration.notifications.digests.clone(),
+            mailer,
+            state.pr_update_service.clone(),
+            cache.clone(),
+        )
+        .start();
+    }
+
+    start_with_config(
+        configuration.port,
+        configuration.paths.static_folder(),
+        configuration.auth.clone(),
+        configuration
+            .bitbucket
+            .as_ref()
+            .and_then(|bitbucket| bitbucket.webhook_secret.clone()),
+        cache,
+        state,
+        tx,
+    )
+    .await?;
 
     Ok(())
 }
 
-async fn setup_db_connection() -> anyhow::Result<DatabaseConnection> {
-    let mut dir = std::env::current_dir().context("Could not get working directory.")?;
-    dir.push(DATA_FOLDER);
-    std::fs::create_dir_all(dir).context("Could not create data folder.")?;
-    let mut connect_options = ConnectOptions::new(format!(
-        "sqlite:./{}/{}?mode=rwc",
-        DATA_FOLDER, DB_FILE_NAME
-    ));
+async fn setup_db_connection(db_file: &Path) -> anyhow::Result<DatabaseConnection> {
+    if let Some(dir) = db_file.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Could not create data folder {}.", dir.display()))?;
+    }
+    info!("Using database file {}.", db_file.display());
+    let mut connect_options = ConnectOptions::new(format!("sqlite:{}?mode=rwc", db_file.display()));
     connect_options.sqlx_logging_level(LevelFilter::Debug);
     Database::connect(connect_options)
         .await
@@ -71,15 +165,21 @@ async fn setup_db_connection() -> anyhow::Result<DatabaseConnection> {
 
 async fn start_with_config(
     port: u16,
+    static_folder: PathBuf,
+    auth_configuration: AuthConfiguration,
+    bitbucket_webhook_secret: Option<String>,
     cache: LockableCache,
-    db_connection: DatabaseConnection,
+    state: AppServicesState,
     reload_sender: UnboundedSender<()>,
 ) -> anyhow::Result<()> {
     info!("Starting git server dashboard...");
-    let pr_event_repository = PullRequestEventRepository::new(db_connection);
-    let pr_event_service = PullRequestUpdateService::new(pr_event_repository);
-
-    let clean_up_pr_service = pr_event_service.clone();
+    if matches!(auth_configuration, AuthConfiguration::None) {
+        warn!("Authentication is disabled, the dashboard and its API are public.");
+    }
+    if bitbucket_webhook_secret.is_none() {
+        warn!("No Bitbucket webhook secret is configured, webhook requests are not verified.");
+    }
+    let clean_up_pr_service = state.pr_update_service.clone();
     // start pr event cleanup job
     tokio::spawn(async move {
         let mut interval = tokio::time::interval(Duration::from_secs(60));
@@ -92,8 +192,15 @@ async fn start_with_config(
         }
     });
 
-    let router = get_router(cache, pr_event_service, reload_sender)
-        .context("Could not configure server routes")?;
+    let router = get_router(
+        cache,
+        state,
+        reload_sender,
+        static_folder,
+        auth_configuration,
+        bitbucket_webhook_secret,
+    )
+    .context("Could not configure server routes")?;
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
     let listener = tokio::net::TcpListener::bind(addr).await?;
     axum::serve(listener, router.into_make_service())
@@ -0,0 +1,90 @@
+use anyhow::Context;
+use chrono::Utc;
+use log::info;
+
+use crate::adapter::db::alerts::AlertRepository;
+use crate::config::AlertRuleConfiguration;
+use crate::service::alerts::model::Alert;
+use crate::service::alerts::rules::{evaluate_rules, StatusSinceMap};
+use crate::service::pipelines::pipeline_history_service::PipelineHistoryService;
+use crate::service::repositories::model::RepositoriesData;
+
+#[derive(Clone)]
+pub struct AlertService {
+    alert_repository: AlertRepository,
+    pipeline_history_service: PipelineHistoryService,
+    rules: Vec<AlertRuleConfiguration>,
+}
+
+impl AlertService {
+    pub fn new(
+        alert_repository: AlertRepository,
+        pipeline_history_service: PipelineHistoryService,
+        rules: Vec<AlertRuleConfiguration>,
+    ) -> Self {
+        Self {
+            alert_repository,
+            pipeline_history_service,
+            rules,
+        }
+    }
+
+    /// Evaluates the rules against freshly loaded data. Alerts whose condition no longer holds are
+    /// cleared, new ones are stored and returned.
+    pub async fn update_alerts(&self, data: &RepositoriesData) -> anyhow::Result<Vec<Alert>> {
+        let status_since: StatusSinceMap = self
+            .pipeline_history_service
+            .get_latest_transitions()
+            .await?
+            .into_iter()
+            .map(|transition| {
+                (
+                    (transition.repository, transition.branch),
+                    transition.timestamp,
+                )
+            })
+            .collect();
+        let current_alerts = evaluate_rules(&self.rules, data, &status_since, Utc::now());
+        let existing_alerts = self.alert_repository.get_alerts().await?;
+
+        let resolved_alerts: Vec<Alert> = existing_alerts
+            .iter()
+            .filter(|existing| {
+                !current_alerts
+                    .iter()
+                    .any(|current| current.is_same_alert(existing))
+            })
+            .cloned()
+            .collect();
+        let new_alerts: Vec<Alert> = current_alerts
+            .into_iter()
+            .filter(|current| {
+                !existing_alerts
+                    .iter()
+                    .any(|existing| existing.is_same_alert(current))
+            })
+            .collect();
+
+        if !resolved_alerts.is_empty() || !new_alerts.is_empty() {
+            info!(
+                "Raising {} new alerts and clearing {} resolved alerts.",
+                new_alerts.len(),
+                resolved_alerts.len()
+            );
+        }
+
+        self.alert_repository
+            .delete_alerts(&resolved_alerts)
+            .await
+            .context("Could not clear resolved alerts.")?;
+        self.alert_repository
+            .save_alerts(&new_alerts)
+            .await
+            .context("Could not save new alerts.")?;
+        Ok(new_alerts)
+    }
+
+    pub async fn get_alerts(&self) -> anyhow::Result<Vec<Alert>> {
+        self.alert_repository.get_alerts().await
+    }
+}
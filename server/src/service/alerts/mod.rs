@@ -0,0 +1,3 @@
+pub mod alert_service;
+pub mod model;
+mod rules;
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+
+pub type AlertTimestamp = DateTime<Utc>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, strum::Display, strum::EnumString)]
+pub enum AlertRuleType {
+    TargetBranchFailed,
+    PullRequestAwaitingApproval,
+    StandaloneBranchWithoutPipeline,
+}
+
+#[derive(Clone, Serialize)]
+pub struct Alert {
+    pub id: Option<i32>,
+    pub rule: AlertRuleType,
+    pub repository: String,
+    /// Branch or PR the alert is about, used together with rule and repository to identify the
+    /// alert.
+    pub subject: String,
+    pub message: String,
+    pub link: Option<String>,
+    pub raised_at: AlertTimestamp,
+}
+
+impl Alert {
+    pub fn is_same_alert(&self, other: &Alert) -> bool {
+        self.rule == other.rule
+            && self.repository == other.repository
+            && self.subject == other.subject
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Duration};
+
+use crate::config::AlertRuleConfiguration;
+use crate::service::alerts::model::{Alert, AlertRuleType, AlertTimestamp};
+use crate::service::repositories::model::{PipelineStatus, RepositoriesData};
+
+/// Time since which each (repository, branch) has had its current pipeline status.
+pub type StatusSinceMap = HashMap<(String, String), AlertTimestamp>;
+
+/// Evaluates the configured rules against the given data and returns an alert for every
+/// condition that currently holds.
+pub fn evaluate_rules(
+    rules: &[AlertRuleConfiguration],
+    data: &RepositoriesData,
+    status_since: &StatusSinceMap,
+    now: AlertTimestamp,
+) -> Vec<Alert> {
+    rules
+        .iter()
+        .flat_map(|rule| match rule {
+            AlertRuleConfiguration::TargetBranchFailed { minutes } => {
+                evaluate_target_branch_failed(*minutes, data, status_since, now)
+            }
+            AlertRuleConfiguration::PullRequestAwaitingApproval { days } => {
+                evaluate_pull_request_awaiting_approval(*days, data, now)
+            }
+            AlertRuleConfiguration::StandaloneBranchWithoutPipeline => {
+                evaluate_standalone_branch_without_pipeline(data, now)
+            }
+        })
+        .collect()
+}
+
+fn evaluate_target_branch_failed(
+    minutes: i64,
+    data: &RepositoriesData,
+    status_since: &StatusSinceMap,
+    now: AlertTimestamp,
+) -> Vec<Alert> {
+    let threshold = now - Duration::minutes(minutes);
+    data.repositories
+        .iter()
+        .flat_map(|repository| {
+            repository
+                .pull_request_target_branches
+                .iter()
+                .filter(|branch| branch.pipeline_status == PipelineStatus::Failed)
+                .filter(|branch| {
+                    let key = (
+                        repository.repository_name.clone(),
+                        branch.branch_name.clone(),
+                    );
+                    // without a recorded transition we cannot tell how long it has been failing
+                    status_since
+                        .get(&key)
+                        .is_some_and(|since| since.le(&threshold))
+                })
+                .map(|branch| Alert {
+                    id: None,
+                    rule: AlertRuleType::TargetBranchFailed,
+                    repository: repository.repository_name.clone(),
+                    subject: branch.branch_name.clone(),
+                    message: format!(
+                        "Pipeline on {} has been failing for more than {} minutes.",
+                        branch.branch_name, minutes
+                    ),
+                    link: branch.pipeline_url.clone(),
+                    raised_at: now,
+                })
+        })
+        .collect()
+}
+
+fn evaluate_pull_request_awaiting_approval(
+    days: i64,
+    data: &RepositoriesData,
+    now: AlertTimestamp,
+) -> Vec<Alert> {
+    let threshold = now - Duration::days(days);
+    data.repositories
+        .iter()
+        .flat_map(|repository| {
+            repository
+                .pull_request_target_branches
+                .iter()
+                .flat_map(|branch| branch.pull_requests.iter().map(move |pr| (branch, pr)))
+                .filter(|(_, pr)| !pr.review.approved)
+                .filter(|(_, pr)| {
+                    DateTime::parse_from_rfc3339(&pr.created_date)
+                        .is_ok_and(|created_date| created_date.le(&threshold))
+                })
+                .map(|(branch, pr)| Alert {
+                    id: None,
+                    rule: AlertRuleType::PullRequestAwaitingApproval,
+                    repository: repository.repository_name.clone(),
+                    // the source branch alone is not unique, it can have PRs into several targets
+                    subject: format!("#{} into {}", pr.number, branch.branch_name),
+                    message: format!(
+                        "PR from {} into {} has been waiting for approval for more than {} days.",
+                        pr.branch_name, branch.branch_name, days
+                    ),
+                    link: Some(pr.link_url.clone()),
+                    raised_at: now,
+                })
+        })
+        .collect()
+}
+
+fn evaluate_standalone_branch_without_pipeline(
+    data: &RepositoriesData,
+    now: AlertTimestamp,
+) -> Vec<Alert> {
+    data.repositories
+        .iter()
+        .flat_map(|repository| {
+            repository
+                .standalone_branches
+                .iter()
+                .filter(|branch| branch.pipeline_status == PipelineStatus::None)
+                .map(|branch| Alert {
+                    id: None,
+                    rule: AlertRuleType::StandaloneBranchWithoutPipeline,
+                    repository: repository.repository_name.clone(),
+                    subject: branch.branch_name.clone(),
+                    message: format!("Branch {} has no pipeline.", branch.branch_name),
+                    link: None,
+                    raised_at: now,
+                })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use crate::config::AlertRuleConfiguration;
+    use crate::service::alerts::model::AlertRuleType;
+    use crate::service::alerts::rules::{evaluate_rules, StatusSinceMap};
+    use crate::service::repositories::model::{
+        PipelineStatus, PullRequest, PullRequestTargetBranch, RepositoriesData,
+        RepositoryBranchData, ReviewSummary,
+    };
+
+    fn get_pull_request(approved: bool, created_days_ago: i64) -> PullRequest {
+        PullRequest {
+            number: 1,
+            title: "Feature".to_string(),
+            description: None,
+            draft: false,
+            branch_name: format!("feature-{created_days_ago}"),
+            user_name: "user".to_string(),
+            user_id: "user".to_string(),
+            user_profile_image: "image".to_string(),
+            comment_count: 0,
+            created_date: (Utc::now() - Duration::days(created_days_ago)).to_rfc3339(),
+            last_activity_date: Utc::now().to_rfc3339(),
+            review: ReviewSummary {
+                approved,
+                ..ReviewSummary::default()
+            },
+            mergeability: None,
+            reviewers: vec![],
+            ahead_behind: None,
+            pipeline_status: PipelineStatus::Successful,
+            pipeline_url: None,
+            pipeline_jobs: vec![],
+            pipeline_timing: None,
+            link_url: "link".to_string(),
+        }
+    }
+
+    fn get_data(
+        target_branch_status: PipelineStatus,
+        pull_requests: Vec<PullRequest>,
+    ) -> RepositoriesData {
+        RepositoriesData {
+            last_updated_date: None,
+            currently_refreshing: false,
+            stale: false,
+            repositories: vec![RepositoryBranchData {
+                repository_name: "repo1".to_string(),
+                repository_url: "url".to_string(),
+                pull_request_target_branches: vec![PullRequestTargetBranch {
+                    branch_name: "main".to_string(),
+                    pipeline_url: None,
+                    pipeline_jobs: vec![],
+                    pipeline_timing: None,
+                    pipeline_status: target_branch_status,
+                    last_commit: None,
+                    pull_requests,
+                }],
+                standalone_branches: vec![],
+            }],
+        }
+    }
+
+    #[test]
+    fn evaluate_rules_target_branch_failed_long_enough() {
+        let rules = vec![AlertRuleConfiguration::TargetBranchFailed { minutes: 30 }];
+        let data = get_data(PipelineStatus::Failed, vec![]);
+        let now = Utc::now();
+        let mut status_since = StatusSinceMap::new();
+
+        status_since.insert(
+            ("repo1".to_string(), "main".to_string()),
+            now - Duration::minutes(10),
+        );
+        assert!(evaluate_rules(&rules, &data, &status_since, now).is_empty());
+
+        status_since.insert(
+            ("repo1".to_string(), "main".to_string()),
+            now - Duration::minutes(31),
+        );
+        let alerts = evaluate_rules(&rules, &data, &status_since, now);
+        assert_eq!(1, alerts.len());
+        assert_eq!(AlertRuleType::TargetBranchFailed, alerts[0].rule);
+        assert_eq!("main", alerts[0].subject);
+    }
+
+    #[test]
+    fn evaluate_rules_pull_request_awaiting_approval() {
+        let rules = vec![AlertRuleConfiguration::PullRequestAwaitingApproval { days: 3 }];
+        let data = get_data(
+            PipelineStatus::Successful,
+            vec![
+                get_pull_request(false, 1),
+                get_pull_request(true, 5),
+                get_pull_request(false, 5),
+            ],
+        );
+
+        let alerts = evaluate_rules(&rules, &data, &StatusSinceMap::new(), Utc::now());
+
+        assert_eq!(1, alerts.len());
+        assert_eq!("#1 into main", alerts[0].subject);
+    }
+
+    #[test]
+    fn evaluate_rules_pull_requests_of_same_branch_into_different_targets() {
+        let rules = vec![AlertRuleConfiguration::PullRequestAwaitingApproval { days: 3 }];
+        let mut data = get_data(PipelineStatus::Successful, vec![get_pull_request(false, 5)]);
+        let mut release_branch = data.repositories[0].pull_request_target_branches[0].clone();
+        release_branch.branch_name = "release".to_string();
+        release_branch.pull_requests[0].number = 2;
+        data.repositories[0]
+            .pull_request_target_branches
+            .push(release_branch);
+
+        let alerts = evaluate_rules(&rules, &data, &StatusSinceMap::new(), Utc::now());
+
+        assert_eq!(2, alerts.len());
+        assert!(!alerts[0].is_same_alert(&alerts[1]));
+    }
+}
@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Context};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use tokio::sync::Mutex;
+
+use crate::adapter::oidc::model::ProviderMetadata;
+use crate::adapter::oidc::{decode_id_token_claims, OidcClient};
+use crate::config::OidcConfiguration;
+use crate::service::auth::model::{AuthenticatedUser, LoginResult};
+
+const PENDING_LOGIN_MINUTES: i64 = 10;
+
+/// A login which was started but not yet completed by the callback from the provider.
+struct PendingLogin {
+    code_verifier: String,
+    nonce: String,
+    return_to: String,
+    expires_at: DateTime<Utc>,
+}
+
+struct Session {
+    user: AuthenticatedUser,
+    expires_at: DateTime<Utc>,
+}
+
+/// Handles the OpenID Connect authorization code flow and the resulting sessions. Sessions are
+/// only kept in memory, users have to log in again after a restart.
+#[derive(Clone)]
+pub struct OidcLoginService {
+    configuration: OidcConfiguration,
+    client: OidcClient,
+    metadata: Arc<Mutex<Option<ProviderMetadata>>>,
+    pending_logins: Arc<Mutex<HashMap<String, PendingLogin>>>,
+    sessions: Arc<Mutex<HashMap<String, Session>>>,
+}
+
+impl OidcLoginService {
+    pub fn new(configuration: OidcConfiguration) -> Self {
+        Self {
+            client: OidcClient::new(&configuration.issuer_url),
+            configuration,
+            metadata: Arc::new(Mutex::new(None)),
+            pending_logins: Arc::new(Mutex::new(HashMap::new())),
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Starts a login and returns the URL of the provider the user has to be redirected to.
+    pub async fn start_login(&self, return_to: &str) -> anyhow::Result<String> {
+        let metadata = self.get_metadata().await?;
+        let state = generate_random_string();
+        let nonce = generate_random_string();
+        let code_verifier = generate_random_string();
+        let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+
+        let authorization_url = reqwest::Url::parse_with_params(
+            &metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", &self.configuration.client_id),
+                ("redirect_uri", &self.configuration.redirect_url),
+                ("scope", &self.configuration.scopes.join(" ")),
+                ("state", &state),
+                ("nonce", &nonce),
+                ("code_challenge", &code_challenge),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .context("Invalid authorization endpoint of OpenID provider.")?;
+
+        let now = Utc::now();
+        let mut pending_logins = self.pending_logins.lock().await;
+        pending_logins.retain(|_, pending_login| pending_login.expires_at > now);
+        pending_logins.insert(
+            state,
+            PendingLogin {
+                code_verifier,
+                nonce,
+                return_to: return_to.to_string(),
+                expires_at: now + Duration::minutes(PENDING_LOGIN_MINUTES),
+            },
+        );
+        Ok(authorization_url.to_string())
+    }
+
+    /// Completes the login the provider redirected back for and creates a session.
+    pub async fn finish_login(&self, code: &str, state: &str) -> anyhow::Result<LoginResult> {
+        let pending_login = self
+            .pending_logins
+            .lock()
+            .await
+            .remove(state)
+            .filter(|pending_login| pending_login.expires_at > Utc::now())
+            .context("Unknown or expired login state.")?;
+
+        let metadata = self.get_metadata().await?;
+        let token_response = self
+            .client
+            .exchange_code(
+                &metadata,
+                &self.configuration.client_id,
+                self.configuration.client_secret.as_deref(),
+                &self.configuration.redirect_url,
+                code,
+                &pending_login.code_verifier,
+            )
+            .await?;
+        let claims = decode_id_token_claims(&token_response.id_token)?;
+
+        if claims.iss != metadata.issuer {
+            return Err(anyhow!("ID token was issued by {}.", claims.iss));
+        }
+        if !claims.aud.contains(&self.configuration.client_id) {
+            return Err(anyhow!("ID token was not issued for this client."));
+        }
+        if claims.exp <= Utc::now().timestamp() {
+            return Err(anyhow!("ID token has expired."));
+        }
+        if claims.nonce.as_deref() != Some(pending_login.nonce.as_str()) {
+            return Err(anyhow!("ID token nonce does not match the login."));
+        }
+
+        let username = claims
+            .preferred_username
+            .unwrap_or_else(|| claims.sub.clone());
+        let user = AuthenticatedUser {
+            name: claims.name.unwrap_or_else(|| username.clone()),
+            username,
+            id: claims.sub,
+            email: claims.email,
+        };
+        let session_id = generate_random_string();
+        let now = Utc::now();
+        let mut sessions = self.sessions.lock().await;
+        sessions.retain(|_, session| session.expires_at > now);
+        sessions.insert(
+            session_id.clone(),
+            Session {
+                user,
+                expires_at: now + self.get_session_duration(),
+            },
+        );
+        Ok(LoginResult {
+            session_id,
+            return_to: pending_login.return_to,
+        })
+    }
+
+    pub async fn get_user(&self, session_id: &str) -> Option<AuthenticatedUser> {
+        self.sessions
+            .lock()
+            .await
+            .get(session_id)
+            .filter(|session| session.expires_at > Utc::now())
+            .map(|session| session.user.clone())
+    }
+
+    pub async fn logout(&self, session_id: &str) {
+        self.sessions.lock().await.remove(session_id);
+    }
+
+    pub fn get_session_duration(&self) -> Duration {
+        Duration::hours(self.configuration.session_hours)
+    }
+
+    /// Whether the session cookie may only be sent over HTTPS.
+    pub fn is_secure(&self) -> bool {
+        self.configuration.redirect_url.starts_with("https://")
+    }
+
+    /// Returns the provider metadata, discovering it on first use so that the dashboard can start
+    /// while the provider is unavailable.
+    async fn get_metadata(&self) -> anyhow::Result<ProviderMetadata> {
+        let mut metadata = self.metadata.lock().await;
+        if let Some(metadata) = metadata.as_ref() {
+            return Ok(metadata.clone());
+        }
+        let discovered = self
+            .client
+            .discover()
+            .await
+            .context("Could not discover OpenID provider metadata.")?;
+        *metadata = Some(discovered.clone());
+        Ok(discovered)
+    }
+}
+
+fn generate_random_string() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Arc;
+
+    use axum::extract::{Query, State};
+    use axum::response::Redirect;
+    use axum::{Form, Json};
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use base64::Engine;
+    use chrono::Utc;
+    use serde_json::json;
+    use sha2::{Digest, Sha256};
+    use tokio::sync::Mutex;
+
+    use crate::config::OidcConfiguration;
+    use crate::service::auth::login_service::OidcLoginService;
+    use crate::test_support::serve_locally;
+
+    const CLIENT_ID: &str = "dashboard";
+    const REDIRECT_URL: &str = "http://dashboard.example.com/auth/callback";
+
+    /// Authorization request parameters the mock IdP received, keyed by the code it issued.
+    type IssuedCodes = Arc<Mutex<HashMap<String, HashMap<String, String>>>>;
+
+    #[derive(Clone)]
+    struct MockIdp {
+        issuer: String,
+        issued_codes: IssuedCodes,
+    }
+
+    /// Local stand-in for an OpenID provider issuing unsigned ID tokens.
+    async fn start_mock_idp() -> String {
+        serve_locally(|issuer| {
+            let idp = MockIdp {
+                issuer: issuer.to_string(),
+                issued_codes: Arc::new(Mutex::new(HashMap::new())),
+            };
+            axum::Router::new()
+                .route(
+                    "/.well-known/openid-configuration",
+                    axum::routing::get(|State(idp): State<MockIdp>| async move {
+                        Json(json!({
+                            "issuer": idp.issuer,
+                            "authorization_endpoint": format!("{}/authorize", idp.issuer),
+                            "token_endpoint": format!("{}/token", idp.issuer),
+                        }))
+                    }),
+                )
+                .route(
+                    "/authorize",
+                    axum::routing::get(
+                        |State(idp): State<MockIdp>,
+                         Query(params): Query<HashMap<String, String>>| async move {
+                            let redirect = format!(
+                                "{}?code=code1&state={}",
+                                params["redirect_uri"], params["state"]
+                            );
+                            idp.issued_codes
+                                .lock()
+                                .await
+                                .insert("code1".to_string(), params);
+                            Redirect::to(&redirect)
+                        },
+                    ),
+                )
+                .route(
+                    "/token",
+                    axum::routing::post(
+                        |State(idp): State<MockIdp>,
+                         Form(params): Form<HashMap<String, String>>| async move {
+                            let authorize_params = idp
+                                .issued_codes
+                                .lock()
+                                .await
+                                .remove(&params["code"])
+                                .unwrap();
+                            let challenge =
+                                URL_SAFE_NO_PAD.encode(Sha256::digest(&params["code_verifier"]));
+                            assert_eq!(authorize_params["code_challenge"], challenge);
+                            let claims = json!({
+                                "iss": idp.issuer,
+                                "sub": "user-1",
+                                "aud": CLIENT_ID,
+                                "exp": Utc::now().timestamp() + 300,
+                                "nonce": authorize_params["nonce"],
+                                "name": "Alice",
+                                "email": "alice@example.com",
+                            });
+                            let id_token = format!(
+                                "{}.{}.",
+                                URL_SAFE_NO_PAD.encode(r#"{"alg":"none"}"#),
+                                URL_SAFE_NO_PAD.encode(claims.to_string())
+                            );
+                            Json(json!({ "id_token": id_token, "access_token": "token" }))
+                        },
+                    ),
+                )
+                .with_state(idp)
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn login_with_mock_idp() {
+        let issuer = start_mock_idp().await;
+        let service = OidcLoginService::new(OidcConfiguration {
+            issuer_url: issuer,
+            client_id: CLIENT_ID.to_string(),
+            client_secret: Some("secret".to_string()),
+            redirect_url: REDIRECT_URL.to_string(),
+            scopes: vec!["openid".to_string()],
+            session_hours: 1,
+        });
+
+        let authorization_url = service.start_login("/pipelines").await.unwrap();
+        let response = reqwest::Client::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .unwrap()
+            .get(authorization_url)
+            .send()
+            .await
+            .unwrap();
+        let callback_url = reqwest::Url::parse(
+            response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .unwrap()
+                .to_str()
+                .unwrap(),
+        )
+        .unwrap();
+        let params: HashMap<String, String> = callback_url.query_pairs().into_owned().collect();
+
+        let login = service
+            .finish_login(&params["code"], &params["state"])
+            .await
+            .unwrap();
+        let user = service.get_user(&login.session_id).await.unwrap();
+
+        assert_eq!("/pipelines", login.return_to);
+        assert_eq!("user-1", user.id);
+        assert_eq!("Alice", user.name);
+        assert_eq!(Some("alice@example.com".to_string()), user.email);
+        // the state can only be used once
+        assert!(service
+            .finish_login(&params["code"], &params["state"])
+            .await
+            .is_err());
+
+        service.logout(&login.session_id).await;
+        assert_eq!(None, service.get_user(&login.session_id).await);
+    }
+}
@@ -0,0 +1,2 @@
+pub mod login_service;
+pub mod model;
@@ -0,0 +1,19 @@
+use serde_derive::Serialize;
+
+/// The user a request was made by, available to handlers as a request extension.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AuthenticatedUser {
+    /// Stable identifier, the OIDC subject or the basic auth user name.
+    pub id: String,
+    /// Login name, used to match the user against authors and reviewers on the git server.
+    pub username: String,
+    pub name: String,
+    pub email: Option<String>,
+}
+
+/// Result of a completed login.
+pub struct LoginResult {
+    pub session_id: String,
+    /// Dashboard path the user originally requested.
+    pub return_to: String,
+}
@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use crate::config::DashboardConfiguration;
+use crate::service::prupdates::model::PullRequestUpdate;
+use crate::service::repositories::branch_filter::branch_name_matches;
+use crate::service::repositories::model::{is_repository_selected, RepositoriesData};
+
+/// Provides the named dashboards, which show a part of the data loaded for the whole instance.
+#[derive(Clone)]
+pub struct DashboardService {
+    dashboards: Arc<Vec<DashboardConfiguration>>,
+}
+
+impl DashboardService {
+    pub fn new(dashboards: Vec<DashboardConfiguration>) -> Self {
+        Self {
+            dashboards: Arc::new(dashboards),
+        }
+    }
+
+    pub fn get_dashboard_names(&self) -> Vec<String> {
+        self.dashboards
+            .iter()
+            .map(|dashboard| dashboard.name.clone())
+            .collect()
+    }
+
+    pub fn get_dashboard(&self, name: &str) -> Option<&DashboardConfiguration> {
+        self.dashboards
+            .iter()
+            .find(|dashboard| dashboard.name == name)
+    }
+}
+
+/// Reduces the data to the repositories and branches of the dashboard.
+pub fn filter_dashboard_data(
+    dashboard: &DashboardConfiguration,
+    mut data: RepositoriesData,
+) -> RepositoriesData {
+    data.repositories.retain(|repository| {
+        is_repository_selected(&dashboard.repositories, &repository.repository_name)
+    });
+    if !dashboard.branches.is_empty() {
+        let is_branch_selected = |branch_name: &str| {
+            dashboard
+                .branches
+                .iter()
+                .any(|pattern| branch_name_matches(pattern, branch_name))
+        };
+        for repository in &mut data.repositories {
+            repository
+                .pull_request_target_branches
+                .retain(|branch| is_branch_selected(&branch.branch_name));
+            repository
+                .standalone_branches
+                .retain(|branch| is_branch_selected(&branch.branch_name));
+        }
+    }
+    data
+}
+
+/// Reduces the PR updates to the ones in the PR update scope of the dashboard.
+pub fn filter_dashboard_pr_updates(
+    dashboard: &DashboardConfiguration,
+    updates: Vec<PullRequestUpdate>,
+) -> Vec<PullRequestUpdate> {
+    let scope = if dashboard.pr_update_repositories.is_empty() {
+        &dashboard.repositories
+    } else {
+        &dashboard.pr_update_repositories
+    };
+    updates
+        .into_iter()
+        .filter(|update| is_repository_selected(scope, &update.repository))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::config::DashboardConfiguration;
+    use crate::service::dashboards::dashboard_service::filter_dashboard_data;
+    use crate::service::repositories::model::{
+        PipelineStatus, RepositoriesData, RepositoryBranchData, StandaloneBranch,
+    };
+
+    fn get_repository(name: &str, branches: &[&str]) -> RepositoryBranchData {
+        RepositoryBranchData {
+            repository_name: name.to_string(),
+            repository_url: "url".to_string(),
+            pull_request_target_branches: vec![],
+            standalone_branches: branches
+                .iter()
+                .map(|branch| StandaloneBranch {
+                    branch_name: branch.to_string(),
+                    pipeline_status: PipelineStatus::Successful,
+                    pipeline_url: None,
+                    pipeline_jobs: vec![],
+                    pipeline_timing: None,
+                    last_commit: None,
+                    ahead_behind: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn filter_dashboard_data_by_repository_and_branch() {
+        let data = RepositoriesData {
+            last_updated_date: None,
+            currently_refreshing: false,
+            stale: false,
+            repositories: vec![
+                get_repository("PROJECT/repo1", &["main", "release/1.0", "feature-1"]),
+                get_repository("PROJECT/repo2", &["main"]),
+            ],
+        };
+        let dashboard = DashboardConfiguration {
+            name: "team-a".to_string(),
+            repositories: vec!["PROJECT/repo1".to_string()],
+            branches: vec!["main".to_string(), "release/*".to_string()],
+            pr_update_repositories: vec![],
+        };
+
+        let filtered = filter_dashboard_data(&dashboard, data);
+
+        assert_eq!(1, filtered.repositories.len());
+        let branches: Vec<&str> = filtered.repositories[0]
+            .standalone_branches
+            .iter()
+            .map(|branch| branch.branch_name.as_str())
+            .collect();
+        assert_eq!(vec!["main", "release/1.0"], branches);
+    }
+}
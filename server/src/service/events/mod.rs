@@ -0,0 +1,37 @@
+use log::debug;
+use tokio::sync::broadcast;
+
+use crate::service::pipelines::model::PipelineStatusChange;
+use crate::service::prupdates::model::PullRequestEvent;
+
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Events other parts of the dashboard (e.g. notifiers) can react to.
+#[derive(Clone)]
+pub enum DashboardEvent {
+    PullRequest(PullRequestEvent),
+    PipelineStatusChanged(PipelineStatusChange),
+}
+
+#[derive(Clone)]
+pub struct DashboardEventPublisher {
+    sender: broadcast::Sender<DashboardEvent>,
+}
+
+impl DashboardEventPublisher {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+        Self { sender }
+    }
+
+    pub fn publish(&self, event: DashboardEvent) {
+        // sending only fails if nobody is subscribed, which is fine
+        if self.sender.send(event).is_err() {
+            debug!("Dropped dashboard event as there are no subscribers.");
+        }
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<DashboardEvent> {
+        self.sender.subscribe()
+    }
+}
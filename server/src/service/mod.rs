@@ -1,2 +1,8 @@
+pub mod alerts;
+pub mod auth;
+pub mod dashboards;
+pub mod events;
+pub mod notifications;
+pub mod pipelines;
 pub mod prupdates;
 pub mod repositories;
@@ -0,0 +1,313 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use log::{error, warn};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::time::Instant;
+
+use crate::adapter::chat::ChatWebhookClient;
+use crate::config::ChatNotificationConfiguration;
+use crate::service::events::DashboardEvent;
+use crate::service::notifications::message::map_notification;
+use crate::service::notifications::model::ChatMessage;
+use crate::service::repositories::model::is_repository_selected;
+
+const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(2);
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Posts notifications about dashboard events to the configured chat webhooks.
+pub struct ChatNotifier {
+    configurations: Vec<ChatNotificationConfiguration>,
+    client: ChatWebhookClient,
+    retry_base_delay: Duration,
+}
+
+impl ChatNotifier {
+    pub fn new(configurations: Vec<ChatNotificationConfiguration>) -> Self {
+        Self {
+            configurations,
+            client: ChatWebhookClient::new(),
+            retry_base_delay: RETRY_BASE_DELAY,
+        }
+    }
+
+    /// Starts one delivery task per webhook and routes the received events to them.
+    pub fn start(self, mut receiver: broadcast::Receiver<DashboardEvent>) {
+        let targets: Vec<(ChatNotificationConfiguration, UnboundedSender<ChatMessage>)> = self
+            .configurations
+            .into_iter()
+            .map(|configuration| {
+                let (sender, message_receiver) = unbounded_channel();
+                tokio::spawn(deliver_messages(
+                    configuration.clone(),
+                    self.client.clone(),
+                    message_receiver,
+                    self.retry_base_delay,
+                ));
+                (configuration, sender)
+            })
+            .collect();
+
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        let Some(notification) = map_notification(&event) else {
+                            continue;
+                        };
+                        targets
+                            .iter()
+                            .filter(|(configuration, _)| {
+                                is_repository_selected(
+                                    &configuration.repositories,
+                                    &notification.repository,
+                                )
+                            })
+                            .for_each(|(configuration, sender)| {
+                                if sender.send(notification.message.clone()).is_err() {
+                                    warn!(
+                                        "Delivery of chat notifications to {} has stopped.",
+                                        configuration.url
+                                    );
+                                }
+                            });
+                    }
+                    Err(RecvError::Lagged(count)) => {
+                        warn!("Chat notifier skipped {} events as it fell behind.", count);
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+}
+
+async fn deliver_messages(
+    configuration: ChatNotificationConfiguration,
+    client: ChatWebhookClient,
+    mut receiver: UnboundedReceiver<ChatMessage>,
+    retry_base_delay: Duration,
+) {
+    let mut rate_limiter = RateLimiter::new(configuration.max_messages_per_minute);
+    while let Some(message) = receiver.recv().await {
+        rate_limiter.wait_for_slot().await;
+
+        let mut attempt = 1;
+        loop {
+            match client
+                .post_message(configuration.kind, &configuration.url, &message)
+                .await
+            {
+                Ok(()) => break,
+                Err(err) if attempt < MAX_DELIVERY_ATTEMPTS => {
+                    warn!(
+                        "Could not post chat notification (attempt {}), will retry: {:#}",
+                        attempt, err
+                    );
+                    tokio::time::sleep(retry_base_delay * 2u32.pow(attempt - 1)).await;
+                    attempt += 1;
+                }
+                Err(err) => {
+                    error!("Could not post chat notification: {:#}", err);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Allows at most `max_messages` within a sliding window of one minute.
+struct RateLimiter {
+    max_messages: usize,
+    sent: VecDeque<Instant>,
+}
+
+impl RateLimiter {
+    fn new(max_messages: usize) -> Self {
+        Self {
+            max_messages: max_messages.max(1),
+            sent: VecDeque::new(),
+        }
+    }
+
+    async fn wait_for_slot(&mut self) {
+        if self.sent.len() >= self.max_messages {
+            if let Some(oldest) = self.sent.pop_front() {
+                tokio::time::sleep_until(oldest + RATE_LIMIT_WINDOW).await;
+            }
+        }
+        let now = Instant::now();
+        while self
+            .sent
+            .front()
+            .is_some_and(|sent| now.duration_since(*sent) >= RATE_LIMIT_WINDOW)
+        {
+            self.sent.pop_front();
+        }
+        self.sent.push_back(now);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::Utc;
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::{ConnectOptions, Database};
+
+    use crate::adapter::db::pipelines::{
+        PipelineRunRepository, PipelineStatusTransitionRepository,
+    };
+    use crate::config::{ChatKind, ChatNotificationConfiguration};
+    use crate::service::events::{DashboardEvent, DashboardEventPublisher};
+    use crate::service::notifications::chat_notifier::{ChatNotifier, RateLimiter};
+    use crate::service::pipelines::pipeline_history_service::PipelineHistoryService;
+    use crate::service::prupdates::model::{PullRequestEvent, PullRequestEventType};
+    use crate::service::repositories::model::{
+        PipelineStatus, PullRequestTargetBranch, RepositoriesData, RepositoryBranchData,
+    };
+    use crate::test_support::StandInServer;
+
+    fn get_pr_event(event_type: PullRequestEventType) -> PullRequestEvent {
+        PullRequestEvent {
+            id: None,
+            pr_id: "pr_1".to_string(),
+            event_type,
+            author: "Jane".to_string(),
+            title: "Fix login".to_string(),
+            repository: "PROJECT/repo1".to_string(),
+            text: "".to_string(),
+            timestamp: Utc::now(),
+            pr_link: "https://git.example.com/pr/1".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn start_routes_and_retries_notifications() {
+        let stand_in = StandInServer::start().await;
+        let publisher = DashboardEventPublisher::new();
+        let mut notifier = ChatNotifier::new(vec![
+            ChatNotificationConfiguration {
+                kind: ChatKind::Slack,
+                url: format!("{}/slack", stand_in.url),
+                repositories: vec!["PROJECT/repo1".to_string()],
+                max_messages_per_minute: 10,
+            },
+            ChatNotificationConfiguration {
+                kind: ChatKind::Teams,
+                url: format!("{}/teams", stand_in.url),
+                repositories: vec!["PROJECT/other-repo".to_string()],
+                max_messages_per_minute: 10,
+            },
+        ]);
+        notifier.retry_base_delay = Duration::from_millis(10);
+        notifier.start(publisher.subscribe());
+
+        publisher.publish(DashboardEvent::PullRequest(get_pr_event(
+            PullRequestEventType::SourceBranchUpdated,
+        )));
+        publisher.publish(DashboardEvent::PullRequest(get_pr_event(
+            PullRequestEventType::Approved,
+        )));
+
+        stand_in.wait_for_requests("/slack", 2).await;
+        // give wrongly routed or repeated deliveries the chance to arrive
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let slack_requests = stand_in.get_requests("/slack").await;
+        assert_eq!(2, slack_requests.len());
+        assert!(stand_in.get_requests("/teams").await.is_empty());
+        let body: serde_json::Value = serde_json::from_str(&slack_requests[1].body).unwrap();
+        assert_eq!(
+            "*<https://git.example.com/pr/1|Jane approved \"Fix login\">*\nPROJECT/repo1",
+            body["text"]
+        );
+    }
+
+    fn get_data_with_target_branch_status(status: PipelineStatus) -> RepositoriesData {
+        RepositoriesData {
+            last_updated_date: None,
+            currently_refreshing: false,
+            stale: false,
+            repositories: vec![RepositoryBranchData {
+                repository_name: "PROJECT/repo1".to_string(),
+                repository_url: "url".to_string(),
+                pull_request_target_branches: vec![PullRequestTargetBranch {
+                    branch_name: "main".to_string(),
+                    pipeline_url: Some("https://ci.example.com/1".to_string()),
+                    pipeline_jobs: vec![],
+                    pipeline_timing: None,
+                    pipeline_status: status,
+                    last_commit: None,
+                    pull_requests: vec![],
+                }],
+                standalone_branches: vec![],
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn start_notifies_once_when_target_branch_turns_failed() {
+        let stand_in = StandInServer::start().await;
+        let db_connection = Database::connect(ConnectOptions::new("sqlite::memory:".to_owned()))
+            .await
+            .unwrap();
+        Migrator::up(&db_connection, None).await.unwrap();
+        let history_service = PipelineHistoryService::new(
+            PipelineStatusTransitionRepository::new(db_connection.clone()),
+            PipelineRunRepository::new(db_connection),
+        );
+        let publisher = DashboardEventPublisher::new();
+        let mut notifier = ChatNotifier::new(vec![ChatNotificationConfiguration {
+            kind: ChatKind::Slack,
+            url: format!("{}/slack", stand_in.url),
+            repositories: vec![],
+            max_messages_per_minute: 10,
+        }]);
+        notifier.retry_base_delay = Duration::from_millis(10);
+        notifier.start(publisher.subscribe());
+
+        // published like on every refresh
+        for status in [
+            PipelineStatus::Successful,
+            PipelineStatus::Failed,
+            PipelineStatus::Failed,
+        ] {
+            let changes = history_service
+                .record_transitions(&get_data_with_target_branch_status(status))
+                .await
+                .unwrap();
+            changes.into_iter().for_each(|change| {
+                publisher.publish(DashboardEvent::PipelineStatusChanged(change))
+            });
+        }
+
+        // the first attempt is rejected by the stand-in and retried
+        stand_in.wait_for_requests("/slack", 2).await;
+        // give a repeated notification the chance to arrive
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let requests = stand_in.get_requests("/slack").await;
+        assert_eq!(2, requests.len());
+        let body: serde_json::Value = serde_json::from_str(&requests[1].body).unwrap();
+        assert_eq!(
+            "*<https://ci.example.com/1|Pipeline failed on main>*\nThe pipeline of target branch main in PROJECT/repo1 failed.",
+            body["text"]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_limiter_waits_for_window() {
+        let mut rate_limiter = RateLimiter::new(2);
+        let start = tokio::time::Instant::now();
+
+        rate_limiter.wait_for_slot().await;
+        rate_limiter.wait_for_slot().await;
+        assert!(start.elapsed() < Duration::from_secs(1));
+
+        rate_limiter.wait_for_slot().await;
+        assert!(start.elapsed() >= Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,386 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Duration as ChronoDuration, DurationRound, TimeZone, Utc};
+use log::{debug, error, info};
+
+use crate::adapter::smtp::SmtpMailer;
+use crate::config::{DigestConfiguration, DigestSchedule};
+use crate::service::prupdates::model::PullRequestUpdate;
+use crate::service::prupdates::pr_event_service::PullRequestUpdateService;
+use crate::service::repositories::model::{is_repository_selected, PipelineStatus};
+use crate::service::repositories::LockableCache;
+
+const SCHEDULE_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Periodically emails each team a digest of PR activity and failing target branches.
+pub struct DigestNotifier {
+    configurations: Vec<DigestConfiguration>,
+    mailer: SmtpMailer,
+    pr_update_service: PullRequestUpdateService,
+    cache: LockableCache,
+}
+
+struct FailingBranch {
+    repository: String,
+    branch: String,
+    pipeline_url: Option<String>,
+}
+
+struct Digest {
+    subject: String,
+    body: String,
+}
+
+impl DigestNotifier {
+    pub fn new(
+        configurations: Vec<DigestConfiguration>,
+        mailer: SmtpMailer,
+        pr_update_service: PullRequestUpdateService,
+        cache: LockableCache,
+    ) -> Self {
+        Self {
+            configurations,
+            mailer,
+            pr_update_service,
+            cache,
+        }
+    }
+
+    pub fn start(self) {
+        tokio::spawn(async move {
+            let start = Utc::now();
+            // (start of the covered period, next scheduled run) per digest
+            let mut runs: Vec<(DateTime<Utc>, DateTime<Utc>)> = self
+                .configurations
+                .iter()
+                .map(|configuration| (start, get_next_run(configuration.schedule, start)))
+                .collect();
+
+            let mut interval = tokio::time::interval(SCHEDULE_CHECK_INTERVAL);
+            loop {
+                interval.tick().await;
+                let now = Utc::now();
+                for (configuration, (since, next_run)) in self.configurations.iter().zip(&mut runs)
+                {
+                    if now < *next_run {
+                        continue;
+                    }
+                    match self.send_digest(configuration, *since).await {
+                        Ok(()) => *since = now,
+                        // the next digest covers this period as well
+                        Err(err) => error!(
+                            "Could not send digest to team {}: {:#}",
+                            configuration.team, err
+                        ),
+                    }
+                    *next_run = get_next_run(configuration.schedule, now);
+                }
+            }
+        });
+    }
+
+    /// Sends the digest covering everything since the given timestamp. Nothing is sent if there
+    /// was no activity and no target branch is failing.
+    async fn send_digest(
+        &self,
+        configuration: &DigestConfiguration,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<()> {
+        let updates: Vec<PullRequestUpdate> = self
+            .pr_update_service
+            .get_pr_updates_since(since)
+            .await?
+            .into_iter()
+            .filter(|update| {
+                is_repository_selected(&configuration.repositories, &update.repository)
+            })
+            .collect();
+
+        let data = self.cache.lock().await.get_cached_data();
+        let failing_branches: Vec<FailingBranch> = data
+            .repositories
+            .iter()
+            .filter(|repository| {
+                is_repository_selected(&configuration.repositories, &repository.repository_name)
+            })
+            .flat_map(|repository| {
+                repository
+                    .pull_request_target_branches
+                    .iter()
+                    .filter(|branch| branch.pipeline_status == PipelineStatus::Failed)
+                    .map(|branch| FailingBranch {
+                        repository: repository.repository_name.clone(),
+                        branch: branch.branch_name.clone(),
+                        pipeline_url: branch.pipeline_url.clone(),
+                    })
+            })
+            .collect();
+
+        if updates.is_empty() && failing_branches.is_empty() {
+            debug!(
+                "Skipping digest for team {} as there is nothing to report.",
+                configuration.team
+            );
+            return Ok(());
+        }
+
+        let digest = format_digest(&configuration.team, &updates, &failing_branches);
+        self.mailer
+            .send(&configuration.recipients, &digest.subject, digest.body)
+            .await?;
+        info!("Sent digest to team {}.", configuration.team);
+        Ok(())
+    }
+}
+
+fn get_next_run(schedule: DigestSchedule, after: DateTime<Utc>) -> DateTime<Utc> {
+    match schedule {
+        DigestSchedule::Hourly => {
+            let hour_start = after
+                .duration_trunc(ChronoDuration::hours(1))
+                .unwrap_or(after);
+            hour_start + ChronoDuration::hours(1)
+        }
+        DigestSchedule::Daily { hour } => {
+            let today = after.date_naive().and_hms_opt(hour, 0, 0);
+            let today_run = today
+                .map(|run| Utc.from_utc_datetime(&run))
+                .unwrap_or(after);
+            if today_run > after {
+                today_run
+            } else {
+                today_run + ChronoDuration::days(1)
+            }
+        }
+    }
+}
+
+fn format_digest(
+    team: &str,
+    updates: &[PullRequestUpdate],
+    failing_branches: &[FailingBranch],
+) -> Digest {
+    let subject = format!(
+        "[{}] Dashboard digest: {} PR updates, {} failing target branches",
+        team,
+        updates.len(),
+        failing_branches.len()
+    );
+
+    let mut grouped_updates: BTreeMap<&str, Vec<&PullRequestUpdate>> = BTreeMap::new();
+    updates.iter().for_each(|update| {
+        grouped_updates
+            .entry(&update.repository)
+            .or_default()
+            .push(update)
+    });
+
+    let mut body = String::new();
+    if !grouped_updates.is_empty() {
+        body.push_str("PR activity\n===========\n");
+        for (repository, updates) in grouped_updates {
+            body.push_str(&format!("\n{repository}\n"));
+            for update in updates {
+                body.push_str(&format!(
+                    "- {} ({}): {}\n  {}\n",
+                    update.title,
+                    update.author,
+                    update.details.join(", "),
+                    update.pr_link
+                ));
+            }
+        }
+    }
+    if !failing_branches.is_empty() {
+        if !body.is_empty() {
+            body.push('\n');
+        }
+        body.push_str("Failing target branches\n=======================\n\n");
+        for branch in failing_branches {
+            body.push_str(&format!("- {}: {}\n", branch.repository, branch.branch));
+            if let Some(pipeline_url) = &branch.pipeline_url {
+                body.push_str(&format!("  {pipeline_url}\n"));
+            }
+        }
+    }
+
+    Digest { subject, body }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use chrono::{Duration, TimeZone, Utc};
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::{ConnectOptions, Database};
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+    use tokio::sync::Mutex;
+
+    use crate::adapter::db::prupdates::PullRequestEventRepository;
+    use crate::adapter::smtp::SmtpMailer;
+    use crate::config::{DigestConfiguration, DigestSchedule, SmtpConfiguration, SmtpTlsMode};
+    use crate::service::events::DashboardEventPublisher;
+    use crate::service::notifications::digest_notifier::{get_next_run, DigestNotifier};
+    use crate::service::prupdates::model::{PullRequestEvent, PullRequestEventType};
+    use crate::service::prupdates::pr_event_service::PullRequestUpdateService;
+    use crate::service::repositories::cache::RepositoriesDataCache;
+    use crate::service::repositories::model::{
+        PipelineStatus, PullRequestTargetBranch, RepositoriesData, RepositoryBranchData,
+    };
+
+    type ReceivedMails = Arc<Mutex<Vec<String>>>;
+
+    /// Minimal local SMTP sink which accepts every message and records its data.
+    async fn start_smtp_sink() -> (u16, ReceivedMails) {
+        let received: ReceivedMails = Arc::new(Mutex::new(Vec::new()));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let sink_received = received.clone();
+        tokio::spawn(async move {
+            loop {
+                let (stream, _) = listener.accept().await.unwrap();
+                let received = sink_received.clone();
+                tokio::spawn(async move {
+                    let (reader, mut writer) = stream.into_split();
+                    let mut lines = BufReader::new(reader).lines();
+                    writer.write_all(b"220 localhost ESMTP\r\n").await.unwrap();
+                    let mut data: Option<String> = None;
+                    while let Ok(Some(line)) = lines.next_line().await {
+                        if let Some(current_data) = data.as_mut() {
+                            if line == "." {
+                                received.lock().await.push(data.take().unwrap());
+                                writer.write_all(b"250 OK\r\n").await.unwrap();
+                            } else {
+                                current_data.push_str(&line);
+                                current_data.push('\n');
+                            }
+                            continue;
+                        }
+                        let command = line.to_uppercase();
+                        let reply: &[u8] = if command.starts_with("DATA") {
+                            data = Some(String::new());
+                            b"354 End data with <CR><LF>.<CR><LF>\r\n"
+                        } else if command.starts_with("QUIT") {
+                            writer.write_all(b"221 Bye\r\n").await.unwrap();
+                            break;
+                        } else {
+                            b"250 OK\r\n"
+                        };
+                        writer.write_all(reply).await.unwrap();
+                    }
+                });
+            }
+        });
+        (port, received)
+    }
+
+    fn get_data_with_failing_target_branch() -> RepositoriesData {
+        RepositoriesData {
+            last_updated_date: None,
+            currently_refreshing: false,
+            stale: false,
+            repositories: vec![RepositoryBranchData {
+                repository_name: "PROJECT/repo1".to_string(),
+                repository_url: "url".to_string(),
+                pull_request_target_branches: vec![PullRequestTargetBranch {
+                    branch_name: "main".to_string(),
+                    pipeline_url: Some("https://ci.example.com/1".to_string()),
+                    pipeline_jobs: vec![],
+                    pipeline_timing: None,
+                    pipeline_status: PipelineStatus::Failed,
+                    last_commit: None,
+                    pull_requests: vec![],
+                }],
+                standalone_branches: vec![],
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn send_digest_to_smtp_sink() {
+        let (port, received) = start_smtp_sink().await;
+        let mailer = SmtpMailer::new(&SmtpConfiguration {
+            host: "127.0.0.1".to_string(),
+            port: Some(port),
+            user: None,
+            password: None,
+            from: "dashboard@example.com".to_string(),
+            tls: SmtpTlsMode::None,
+        })
+        .unwrap();
+
+        let db_connection = Database::connect(ConnectOptions::new("sqlite::memory:".to_owned()))
+            .await
+            .unwrap();
+        Migrator::up(&db_connection, None).await.unwrap();
+        let pr_update_service = PullRequestUpdateService::new(
+            PullRequestEventRepository::new(db_connection),
+            DashboardEventPublisher::new(),
+        );
+        pr_update_service
+            .save_pr_event(PullRequestEvent {
+                id: None,
+                pr_id: "pr_1".to_string(),
+                event_type: PullRequestEventType::Approved,
+                author: "Jane".to_string(),
+                title: "Fix login".to_string(),
+                repository: "PROJECT/repo1".to_string(),
+                text: "".to_string(),
+                timestamp: Utc::now(),
+                pr_link: "https://git.example.com/pr/1".to_string(),
+            })
+            .await
+            .unwrap();
+
+        let mut cache = RepositoriesDataCache::new();
+        cache.cache_data(get_data_with_failing_target_branch());
+        let configuration = DigestConfiguration {
+            team: "Team A".to_string(),
+            recipients: vec!["team-a@example.com".to_string()],
+            schedule: DigestSchedule::Hourly,
+            repositories: vec!["PROJECT/repo1".to_string()],
+        };
+        let notifier = DigestNotifier::new(
+            vec![configuration.clone()],
+            mailer,
+            pr_update_service,
+            Arc::new(tokio::sync::Mutex::new(cache)),
+        );
+
+        notifier
+            .send_digest(&configuration, Utc::now() - Duration::hours(1))
+            .await
+            .unwrap();
+
+        let received = received.lock().await;
+        assert_eq!(1, received.len());
+        let mail = &received[0];
+        assert!(mail.contains(
+            "Subject: [Team A] Dashboard digest: 1 PR updates, 1 failing target branches"
+        ));
+        assert!(mail.contains("- Fix login (Jane): PR approved"));
+        assert!(mail.contains("- PROJECT/repo1: main"));
+    }
+
+    #[test]
+    fn get_next_run_daily() {
+        let schedule = DigestSchedule::Daily { hour: 8 };
+        let before = Utc.with_ymd_and_hms(2024, 5, 1, 7, 30, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2024, 5, 1, 8, 0, 0).unwrap();
+
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 5, 1, 8, 0, 0).unwrap(),
+            get_next_run(schedule, before)
+        );
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 5, 2, 8, 0, 0).unwrap(),
+            get_next_run(schedule, after)
+        );
+        assert_eq!(
+            Utc.with_ymd_and_hms(2024, 5, 1, 8, 0, 0).unwrap(),
+            get_next_run(DigestSchedule::Hourly, before)
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use crate::service::events::DashboardEvent;
+use crate::service::notifications::model::{ChatMessage, Notification};
+use crate::service::pipelines::model::PipelineStatusChange;
+use crate::service::prupdates::model::{PullRequestEvent, PullRequestEventType};
+use crate::service::repositories::model::PipelineStatus;
+
+/// Maps the events worth notifying about to a message, all other events are ignored.
+pub fn map_notification(event: &DashboardEvent) -> Option<Notification> {
+    match event {
+        DashboardEvent::PullRequest(pr_event) => map_pull_request_notification(pr_event),
+        DashboardEvent::PipelineStatusChanged(change) => map_pipeline_notification(change),
+    }
+}
+
+fn map_pull_request_notification(event: &PullRequestEvent) -> Option<Notification> {
+    let title = match event.event_type {
+        PullRequestEventType::Approved => format!("{} approved \"{}\"", event.author, event.title),
+        PullRequestEventType::CommentAdded => {
+            format!("{} commented on \"{}\"", event.author, event.title)
+        }
+        _ => return None,
+    };
+    let text = match event.text.is_empty() {
+        true => event.repository.clone(),
+        false => format!("{}: {}", event.repository, event.text),
+    };
+    Some(Notification {
+        repository: event.repository.clone(),
+        message: ChatMessage {
+            title,
+            text,
+            link: Some(event.pr_link.clone()),
+        },
+    })
+}
+
+fn map_pipeline_notification(change: &PipelineStatusChange) -> Option<Notification> {
+    let transition = &change.transition;
+    // only notify about target branches which were fine before and not on the first observation
+    let turned_failed = transition.status == PipelineStatus::Failed
+        && change
+            .previous_status
+            .is_some_and(|status| status != PipelineStatus::Failed);
+    if !change.pull_request_target_branch || !turned_failed {
+        return None;
+    }
+    Some(Notification {
+        repository: transition.repository.clone(),
+        message: ChatMessage {
+            title: format!("Pipeline failed on {}", transition.branch),
+            text: format!(
+                "The pipeline of target branch {} in {} failed.",
+                transition.branch, transition.repository
+            ),
+            link: transition.pipeline_url.clone(),
+        },
+    })
+}
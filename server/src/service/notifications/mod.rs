@@ -0,0 +1,5 @@
+pub mod chat_notifier;
+pub mod digest_notifier;
+mod message;
+pub mod model;
+pub mod webhook_service;
@@ -0,0 +1,61 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+
+use crate::service::prupdates::model::PullRequestEvent;
+use crate::service::repositories::model::PipelineStatus;
+
+pub type WebhookTimestamp = DateTime<Utc>;
+
+/// A formatted message, independent of the chat tool it is posted to.
+#[derive(Clone, Debug)]
+pub struct ChatMessage {
+    pub title: String,
+    pub text: String,
+    pub link: Option<String>,
+}
+
+/// A message together with the repository it is about, used to route it.
+pub struct Notification {
+    pub repository: String,
+    pub message: ChatMessage,
+}
+
+/// Normalized JSON body sent to generic webhooks.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WebhookPayload {
+    PullRequest(PullRequestEvent),
+    PipelineStatusChanged(PipelineStatusChangedPayload),
+}
+
+#[derive(Serialize)]
+pub struct PipelineStatusChangedPayload {
+    pub repository: String,
+    pub branch: String,
+    pub status: PipelineStatus,
+    pub previous_status: Option<PipelineStatus>,
+    pub pipeline_url: Option<String>,
+    pub pull_request_target_branch: bool,
+    pub timestamp: WebhookTimestamp,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, strum::Display, strum::EnumString)]
+pub enum WebhookDeliveryStatus {
+    Pending,
+    Delivered,
+    Failed,
+}
+
+#[derive(Clone, Serialize)]
+pub struct WebhookDelivery {
+    pub id: Option<i32>,
+    pub url: String,
+    pub event_type: String,
+    pub payload: String,
+    pub status: WebhookDeliveryStatus,
+    pub attempts: u32,
+    pub response_status: Option<u16>,
+    pub last_error: Option<String>,
+    pub created_at: WebhookTimestamp,
+    pub updated_at: WebhookTimestamp,
+}
@@ -0,0 +1,341 @@
+use std::time::Duration;
+
+use anyhow::Context;
+use chrono::Utc;
+use log::{error, warn};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+use crate::adapter::db::webhooks::WebhookDeliveryRepository;
+use crate::adapter::outbound_webhook::OutboundWebhookClient;
+use crate::config::{WebhookConfiguration, WebhookEventType};
+use crate::service::events::DashboardEvent;
+use crate::service::notifications::model::{
+    PipelineStatusChangedPayload, WebhookDelivery, WebhookDeliveryStatus, WebhookPayload,
+};
+use crate::service::repositories::model::is_repository_selected;
+
+const MAX_DELIVERY_ATTEMPTS: u32 = 5;
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+const DELIVERY_LOG_LIMIT: u64 = 100;
+const DELIVERY_RETENTION_PER_WEBHOOK: u64 = 1000;
+
+/// A serialized event ready to be delivered.
+#[derive(Clone)]
+struct WebhookMessage {
+    event_type: String,
+    body: String,
+}
+
+/// Forwards dashboard events to the configured generic webhooks and keeps a log of deliveries.
+#[derive(Clone)]
+pub struct WebhookService {
+    configurations: Vec<WebhookConfiguration>,
+    client: OutboundWebhookClient,
+    delivery_repository: WebhookDeliveryRepository,
+    retry_base_delay: Duration,
+}
+
+impl WebhookService {
+    pub fn new(
+        configurations: Vec<WebhookConfiguration>,
+        delivery_repository: WebhookDeliveryRepository,
+    ) -> Self {
+        Self {
+            configurations,
+            client: OutboundWebhookClient::new(),
+            delivery_repository,
+            retry_base_delay: RETRY_BASE_DELAY,
+        }
+    }
+
+    /// Starts one delivery task per webhook and routes the received events to them.
+    pub fn start(&self, mut receiver: broadcast::Receiver<DashboardEvent>) {
+        let targets: Vec<(WebhookConfiguration, UnboundedSender<WebhookMessage>)> = self
+            .configurations
+            .iter()
+            .map(|configuration| {
+                let (sender, message_receiver) = unbounded_channel();
+                tokio::spawn(
+                    self.clone()
+                        .deliver_messages(configuration.clone(), message_receiver),
+                );
+                (configuration.clone(), sender)
+            })
+            .collect();
+
+        tokio::spawn(async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        let (event_type, repository, payload) = map_payload(event);
+                        let message = match serde_json::to_string(&payload) {
+                            Ok(body) => WebhookMessage {
+                                event_type: get_event_type_name(event_type).to_string(),
+                                body,
+                            },
+                            Err(err) => {
+                                error!("Could not serialize webhook payload: {:#}", err);
+                                continue;
+                            }
+                        };
+                        targets
+                            .iter()
+                            .filter(|(configuration, _)| {
+                                (configuration.events.is_empty()
+                                    || configuration.events.contains(&event_type))
+                                    && is_repository_selected(
+                                        &configuration.repositories,
+                                        &repository,
+                                    )
+                            })
+                            .for_each(|(configuration, sender)| {
+                                if sender.send(message.clone()).is_err() {
+                                    warn!(
+                                        "Delivery of webhook events to {} has stopped.",
+                                        configuration.url
+                                    );
+                                }
+                            });
+                    }
+                    Err(RecvError::Lagged(count)) => {
+                        warn!(
+                            "Webhook forwarder skipped {} events as it fell behind.",
+                            count
+                        );
+                    }
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+    }
+
+    pub async fn get_deliveries(&self) -> anyhow::Result<Vec<WebhookDelivery>> {
+        self.delivery_repository
+            .get_deliveries(DELIVERY_LOG_LIMIT)
+            .await
+    }
+
+    async fn deliver_messages(
+        self,
+        configuration: WebhookConfiguration,
+        mut receiver: UnboundedReceiver<WebhookMessage>,
+    ) {
+        while let Some(message) = receiver.recv().await {
+            if let Err(err) = self.deliver(&configuration, message).await {
+                error!(
+                    "Could not deliver webhook to {}: {:#}",
+                    configuration.url, err
+                );
+            }
+            // prune once the queued messages are delivered instead of after every single one
+            if receiver.is_empty() {
+                if let Err(err) = self
+                    .delivery_repository
+                    .prune_deliveries(&configuration.url, DELIVERY_RETENTION_PER_WEBHOOK)
+                    .await
+                {
+                    error!(
+                        "Could not prune webhook delivery log of {}: {:#}",
+                        configuration.url, err
+                    );
+                }
+            }
+        }
+    }
+
+    async fn deliver(
+        &self,
+        configuration: &WebhookConfiguration,
+        message: WebhookMessage,
+    ) -> anyhow::Result<()> {
+        let now = Utc::now();
+        let mut delivery = WebhookDelivery {
+            id: None,
+            url: configuration.url.clone(),
+            event_type: message.event_type,
+            payload: message.body,
+            status: WebhookDeliveryStatus::Pending,
+            attempts: 0,
+            response_status: None,
+            last_error: None,
+            created_at: now,
+            updated_at: now,
+        };
+        let delivery_id = self
+            .delivery_repository
+            .create_delivery(&delivery)
+            .await
+            .context("Could not log webhook delivery.")?;
+        delivery.id = Some(delivery_id);
+
+        while delivery.status == WebhookDeliveryStatus::Pending {
+            if delivery.attempts > 0 {
+                let delay = self.retry_base_delay * 2u32.pow(delivery.attempts - 1);
+                tokio::time::sleep(delay).await;
+            }
+            let result = self
+                .client
+                .post(
+                    &configuration.url,
+                    configuration.secret.as_deref(),
+                    &delivery.event_type,
+                    delivery_id,
+                    &delivery.payload,
+                )
+                .await;
+
+            delivery.attempts += 1;
+            delivery.updated_at = Utc::now();
+            match result {
+                Ok(status) if (200..300).contains(&status) => {
+                    delivery.response_status = Some(status);
+                    delivery.last_error = None;
+                    delivery.status = WebhookDeliveryStatus::Delivered;
+                }
+                Ok(status) => {
+                    delivery.response_status = Some(status);
+                    delivery.last_error = Some(format!("Unsuccessful response status {status}."));
+                }
+                Err(err) => {
+                    delivery.response_status = None;
+                    delivery.last_error = Some(format!("{err:#}"));
+                }
+            }
+            if delivery.status == WebhookDeliveryStatus::Pending
+                && delivery.attempts >= MAX_DELIVERY_ATTEMPTS
+            {
+                delivery.status = WebhookDeliveryStatus::Failed;
+            }
+
+            self.delivery_repository
+                .update_delivery(&delivery)
+                .await
+                .context("Could not update webhook delivery log.")?;
+        }
+        Ok(())
+    }
+}
+
+fn map_payload(event: DashboardEvent) -> (WebhookEventType, String, WebhookPayload) {
+    match event {
+        DashboardEvent::PullRequest(pr_event) => (
+            WebhookEventType::PullRequest,
+            pr_event.repository.clone(),
+            WebhookPayload::PullRequest(pr_event),
+        ),
+        DashboardEvent::PipelineStatusChanged(change) => {
+            let transition = change.transition;
+            (
+                WebhookEventType::PipelineStatusChanged,
+                transition.repository.clone(),
+                WebhookPayload::PipelineStatusChanged(PipelineStatusChangedPayload {
+                    repository: transition.repository,
+                    branch: transition.branch,
+                    status: transition.status,
+                    previous_status: change.previous_status,
+                    pipeline_url: transition.pipeline_url,
+                    pull_request_target_branch: change.pull_request_target_branch,
+                    timestamp: transition.timestamp,
+                }),
+            )
+        }
+    }
+}
+
+fn get_event_type_name(event_type: WebhookEventType) -> &'static str {
+    match event_type {
+        WebhookEventType::PullRequest => "pull_request",
+        WebhookEventType::PipelineStatusChanged => "pipeline_status_changed",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use chrono::Utc;
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::{ConnectOptions, Database};
+
+    use crate::adapter::db::webhooks::WebhookDeliveryRepository;
+    use crate::adapter::outbound_webhook::{sign_payload, SIGNATURE_HEADER};
+    use crate::config::{WebhookConfiguration, WebhookEventType};
+    use crate::service::events::{DashboardEvent, DashboardEventPublisher};
+    use crate::service::notifications::model::WebhookDeliveryStatus;
+    use crate::service::notifications::webhook_service::WebhookService;
+    use crate::service::pipelines::model::{PipelineStatusChange, PipelineStatusTransition};
+    use crate::service::repositories::model::PipelineStatus;
+    use crate::test_support::StandInServer;
+
+    fn get_pipeline_change() -> PipelineStatusChange {
+        PipelineStatusChange {
+            previous_status: Some(PipelineStatus::Successful),
+            pull_request_target_branch: true,
+            transition: PipelineStatusTransition {
+                id: None,
+                repository: "repo1".to_string(),
+                branch: "main".to_string(),
+                status: PipelineStatus::Failed,
+                pipeline_url: None,
+                timestamp: Utc::now(),
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn start_delivers_signed_payload_with_retry() {
+        let stand_in = StandInServer::start().await;
+        let db_connection = Database::connect(ConnectOptions::new("sqlite::memory:".to_owned()))
+            .await
+            .unwrap();
+        Migrator::up(&db_connection, None).await.unwrap();
+        let mut service = WebhookService::new(
+            vec![WebhookConfiguration {
+                url: format!("{}/hook", stand_in.url),
+                secret: Some("secret".to_string()),
+                events: vec![WebhookEventType::PipelineStatusChanged],
+                repositories: vec![],
+            }],
+            WebhookDeliveryRepository::new(db_connection),
+        );
+        service.retry_base_delay = Duration::from_millis(10);
+        let publisher = DashboardEventPublisher::new();
+        service.start(publisher.subscribe());
+
+        publisher.publish(DashboardEvent::PipelineStatusChanged(get_pipeline_change()));
+
+        let mut deliveries = vec![];
+        for _ in 0..100 {
+            deliveries = service.get_deliveries().await.unwrap();
+            if deliveries
+                .first()
+                .is_some_and(|delivery| delivery.status != WebhookDeliveryStatus::Pending)
+            {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        assert_eq!(1, deliveries.len());
+        assert_eq!(WebhookDeliveryStatus::Delivered, deliveries[0].status);
+        assert_eq!(2, deliveries[0].attempts);
+        assert_eq!(Some(204), deliveries[0].response_status);
+
+        let requests = stand_in.get_requests("/hook").await;
+        let payload: serde_json::Value = serde_json::from_str(&requests[1].body).unwrap();
+        assert_eq!("pipeline_status_changed", payload["type"]);
+        assert_eq!("Failed", payload["status"]);
+        assert_eq!("Successful", payload["previous_status"]);
+        assert_eq!(
+            sign_payload("secret", &requests[1].body).unwrap(),
+            requests[1]
+                .headers
+                .get(SIGNATURE_HEADER)
+                .unwrap()
+                .to_str()
+                .unwrap()
+        );
+    }
+}
@@ -0,0 +1,80 @@
+use chrono::{DateTime, Utc};
+use serde_derive::Serialize;
+
+use crate::service::repositories::model::PipelineStatus;
+
+pub type PipelineTimestamp = DateTime<Utc>;
+
+/// A change of the pipeline status observed on a branch.
+#[derive(Clone, Serialize)]
+pub struct PipelineStatusTransition {
+    pub id: Option<i32>,
+    pub repository: String,
+    pub branch: String,
+    pub status: PipelineStatus,
+    pub pipeline_url: Option<String>,
+    pub timestamp: PipelineTimestamp,
+}
+
+#[derive(Serialize)]
+pub struct BranchPipelineHistory {
+    pub repository: String,
+    pub branch: String,
+    pub status: PipelineStatus,
+    pub status_since: PipelineTimestamp,
+    pub transitions: Vec<PipelineStatusTransition>,
+}
+
+/// A recorded transition together with what is known about the branch before it.
+#[derive(Clone)]
+pub struct PipelineStatusChange {
+    /// `None` if the branch has not been seen before.
+    pub previous_status: Option<PipelineStatus>,
+    pub pull_request_target_branch: bool,
+    pub transition: PipelineStatusTransition,
+}
+
+/// A finished pipeline of a branch. A commit has several runs if its pipeline was retried.
+#[derive(Clone, Serialize)]
+pub struct PipelineRun {
+    pub repository: String,
+    pub branch: String,
+    pub commit_sha: String,
+    pub status: PipelineStatus,
+    pub pipeline_url: Option<String>,
+    pub started_date: Option<PipelineTimestamp>,
+    pub finished_date: PipelineTimestamp,
+    /// Not known for Bitbucket builds which do not report their duration.
+    pub duration_seconds: Option<u64>,
+}
+
+/// Durations of the recent successful pipelines of a branch.
+#[derive(Serialize)]
+pub struct BranchPipelineStatistics {
+    pub repository: String,
+    pub branch: String,
+    /// Number of runs the statistics are based on, at most the size of the rolling window.
+    pub run_count: usize,
+    pub latest_duration_seconds: u64,
+    pub median_duration_seconds: u64,
+    pub p90_duration_seconds: u64,
+    /// Median of the window before the current one, to tell whether pipelines got slower.
+    pub previous_median_duration_seconds: Option<u64>,
+}
+
+/// How often the recent pipelines of a branch changed their outcome without a reason in the code.
+#[derive(Serialize)]
+pub struct BranchPipelineFlakiness {
+    pub repository: String,
+    pub branch: String,
+    /// Number of successful and failed runs the score is based on.
+    pub run_count: usize,
+    /// Commits whose pipeline failed and succeeded on a retry.
+    pub retried_commits: Vec<String>,
+    /// Share of consecutive runs with a different outcome.
+    pub flip_rate: f64,
+    /// Between 0 and 1: the share of commits which needed a retry to succeed or the flip rate,
+    /// whichever is higher.
+    pub flakiness_score: f64,
+    pub flaky: bool,
+}
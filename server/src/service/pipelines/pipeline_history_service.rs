@@ -0,0 +1,540 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+
+use crate::adapter::db::pipelines::{PipelineRunRepository, PipelineStatusTransitionRepository};
+use crate::service::pipelines::model::{
+    BranchPipelineFlakiness, BranchPipelineHistory, BranchPipelineStatistics, PipelineRun,
+    PipelineStatusChange, PipelineStatusTransition,
+};
+use crate::service::repositories::model::{PipelineStatus, PipelineTiming, RepositoriesData};
+
+/// Number of recent runs the duration statistics of a branch are computed from.
+const STATISTICS_WINDOW: usize = 20;
+/// Number of recent runs the flakiness of a branch is computed from.
+const FLAKINESS_WINDOW: usize = 50;
+/// Share of consecutive runs with a different outcome from which a branch counts as flaky.
+const FLAKY_FLIP_RATE: f64 = 0.3;
+/// Flips in fewer runs may well be real breakages and their fixes.
+const FLAKY_MIN_RUNS: usize = 5;
+/// Number of runs kept per branch, enough for both windows even with canceled or skipped runs.
+const RUN_RETENTION_PER_BRANCH: u64 = 200;
+
+#[derive(Clone)]
+pub struct PipelineHistoryService {
+    transition_repository: PipelineStatusTransitionRepository,
+    run_repository: PipelineRunRepository,
+}
+
+impl PipelineHistoryService {
+    pub fn new(
+        transition_repository: PipelineStatusTransitionRepository,
+        run_repository: PipelineRunRepository,
+    ) -> Self {
+        Self {
+            transition_repository,
+            run_repository,
+        }
+    }
+
+    /// Stores the outcome of the finished pipelines in the freshly loaded data and drops the
+    /// oldest runs of every branch. Runs which were stored by an earlier refresh are skipped.
+    pub async fn record_runs(&self, data: &RepositoriesData) -> anyhow::Result<()> {
+        let runs: Vec<PipelineRun> = get_branch_pipelines(data)
+            .into_iter()
+            .filter_map(|observed| {
+                let timing = observed.pipeline_timing?;
+                let finished_date = DateTime::parse_from_rfc3339(timing.finished_date.as_ref()?)
+                    .ok()?
+                    .with_timezone(&Utc);
+                Some(PipelineRun {
+                    repository: observed.repository,
+                    branch: observed.branch,
+                    commit_sha: timing.commit_sha,
+                    status: observed.status,
+                    pipeline_url: observed.pipeline_url,
+                    started_date: timing
+                        .started_date
+                        .and_then(|date| DateTime::parse_from_rfc3339(&date).ok())
+                        .map(|date| date.with_timezone(&Utc)),
+                    finished_date,
+                    duration_seconds: timing.duration_seconds,
+                })
+            })
+            .collect();
+        self.run_repository
+            .save_runs(&runs)
+            .await
+            .context("Could not save pipeline runs.")?;
+        self.run_repository
+            .prune_runs(RUN_RETENTION_PER_BRANCH)
+            .await
+            .context("Could not prune pipeline runs.")
+    }
+
+    /// Rates how flaky the pipelines of every branch of the repository (or only the given one)
+    /// are, based on their recent successful and failed runs.
+    pub async fn get_flakiness(
+        &self,
+        repository: &str,
+        branch: Option<&str>,
+    ) -> anyhow::Result<Vec<BranchPipelineFlakiness>> {
+        let runs = self
+            .run_repository
+            .get_recent_runs(
+                repository,
+                branch,
+                &[PipelineStatus::Successful, PipelineStatus::Failed],
+                FLAKINESS_WINDOW as u64,
+            )
+            .await?;
+
+        // sorted by branch name to achieve a stable order
+        let mut runs_by_branch: BTreeMap<String, Vec<PipelineRun>> = BTreeMap::new();
+        runs.into_iter().for_each(|run| {
+            runs_by_branch
+                .entry(run.branch.clone())
+                .or_default()
+                .push(run)
+        });
+
+        Ok(runs_by_branch
+            .into_iter()
+            .map(|(branch, runs)| get_flakiness(repository, branch, &runs))
+            .collect())
+    }
+
+    /// Computes the duration statistics of every branch of the repository (or only the given
+    /// one), based on its recent successful pipelines. Failed pipelines are left out, as they
+    /// often stop early.
+    pub async fn get_statistics(
+        &self,
+        repository: &str,
+        branch: Option<&str>,
+    ) -> anyhow::Result<Vec<BranchPipelineStatistics>> {
+        // the current window and the previous one to compare with
+        let runs = self
+            .run_repository
+            .get_recent_runs(
+                repository,
+                branch,
+                &[PipelineStatus::Successful],
+                2 * STATISTICS_WINDOW as u64,
+            )
+            .await?;
+
+        // sorted by branch name to achieve a stable order
+        let mut durations_by_branch: BTreeMap<String, Vec<u64>> = BTreeMap::new();
+        runs.into_iter().for_each(|run| {
+            if let Some(duration_seconds) = run.duration_seconds {
+                durations_by_branch
+                    .entry(run.branch)
+                    .or_default()
+                    .push(duration_seconds)
+            }
+        });
+
+        let statistics = durations_by_branch
+            .into_iter()
+            .filter_map(|(branch, durations)| {
+                let latest_duration_seconds = *durations.last()?;
+                let window_start = durations.len().saturating_sub(STATISTICS_WINDOW);
+                let (previous, current) = durations.split_at(window_start);
+                let previous = &previous[previous.len().saturating_sub(STATISTICS_WINDOW)..];
+                Some(BranchPipelineStatistics {
+                    repository: repository.to_string(),
+                    branch,
+                    run_count: current.len(),
+                    latest_duration_seconds,
+                    median_duration_seconds: percentile(current, 50)?,
+                    p90_duration_seconds: percentile(current, 90)?,
+                    previous_median_duration_seconds: percentile(previous, 50),
+                })
+            })
+            .collect();
+        Ok(statistics)
+    }
+
+    /// Compares the pipeline statuses in the freshly loaded data with the last known status of
+    /// every branch and stores (and returns) the ones that changed.
+    pub async fn record_transitions(
+        &self,
+        data: &RepositoriesData,
+    ) -> anyhow::Result<Vec<PipelineStatusChange>> {
+        let last_known_statuses: HashMap<(String, String), PipelineStatus> = self
+            .get_latest_transitions()
+            .await?
+            .into_iter()
+            .map(|transition| {
+                (
+                    (transition.repository, transition.branch),
+                    transition.status,
+                )
+            })
+            .collect();
+
+        let timestamp = Utc::now();
+        let changes: Vec<PipelineStatusChange> = get_branch_pipelines(data)
+            .into_iter()
+            .filter_map(|observed| {
+                let key = (observed.repository.clone(), observed.branch.clone());
+                let previous_status = last_known_statuses.get(&key).copied();
+                if previous_status == Some(observed.status) {
+                    return None;
+                }
+                Some(PipelineStatusChange {
+                    previous_status,
+                    pull_request_target_branch: observed.pull_request_target_branch,
+                    transition: PipelineStatusTransition {
+                        id: None,
+                        repository: observed.repository,
+                        branch: observed.branch,
+                        status: observed.status,
+                        pipeline_url: observed.pipeline_url,
+                        timestamp,
+                    },
+                })
+            })
+            .collect();
+
+        let transitions: Vec<PipelineStatusTransition> = changes
+            .iter()
+            .map(|change| change.transition.clone())
+            .collect();
+        self.transition_repository
+            .save_transitions(&transitions)
+            .await
+            .context("Could not save pipeline status transitions.")?;
+        Ok(changes)
+    }
+
+    /// Returns the last transition of every branch, i.e. its current status and since when.
+    pub async fn get_latest_transitions(&self) -> anyhow::Result<Vec<PipelineStatusTransition>> {
+        self.transition_repository.get_latest_transitions().await
+    }
+
+    pub async fn get_history(
+        &self,
+        repository: &str,
+        branch: Option<&str>,
+    ) -> anyhow::Result<Vec<BranchPipelineHistory>> {
+        let transitions = self
+            .transition_repository
+            .get_transitions(repository, branch)
+            .await?;
+
+        let mut grouped_transitions: HashMap<String, Vec<PipelineStatusTransition>> =
+            HashMap::new();
+        transitions.into_iter().for_each(|transition| {
+            grouped_transitions
+                .entry(transition.branch.clone())
+                .or_default()
+                .push(transition)
+        });
+
+        // sort by branch name to achieve a stable order
+        let mut map_entries: Vec<(String, Vec<PipelineStatusTransition>)> =
+            grouped_transitions.into_iter().collect();
+        map_entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let histories = map_entries
+            .into_iter()
+            .filter_map(|(branch, transitions)| {
+                let last_transition = transitions.last()?;
+                Some(BranchPipelineHistory {
+                    repository: repository.to_string(),
+                    status: last_transition.status,
+                    status_since: last_transition.timestamp,
+                    branch,
+                    transitions,
+                })
+            })
+            .collect();
+        Ok(histories)
+    }
+}
+
+/// Rates the outcomes of the runs of a branch, ordered from oldest to newest.
+fn get_flakiness(
+    repository: &str,
+    branch: String,
+    runs: &[PipelineRun],
+) -> BranchPipelineFlakiness {
+    let mut retried_commits: Vec<String> = Vec::new();
+    let mut failed_commits: HashSet<&str> = HashSet::new();
+    let mut commits: HashSet<&str> = HashSet::new();
+    for run in runs {
+        commits.insert(&run.commit_sha);
+        match run.status {
+            PipelineStatus::Failed => {
+                failed_commits.insert(&run.commit_sha);
+            }
+            PipelineStatus::Successful
+                if failed_commits.contains(run.commit_sha.as_str())
+                    && !retried_commits.contains(&run.commit_sha) =>
+            {
+                retried_commits.push(run.commit_sha.clone());
+            }
+            _ => {}
+        }
+    }
+
+    let flips = runs
+        .windows(2)
+        .filter(|pair| pair[0].status != pair[1].status)
+        .count();
+    let flip_rate = if runs.len() > 1 {
+        flips as f64 / (runs.len() - 1) as f64
+    } else {
+        0.0
+    };
+    let retried_share = if commits.is_empty() {
+        0.0
+    } else {
+        retried_commits.len() as f64 / commits.len() as f64
+    };
+
+    BranchPipelineFlakiness {
+        repository: repository.to_string(),
+        branch,
+        run_count: runs.len(),
+        flaky: !retried_commits.is_empty()
+            || (runs.len() >= FLAKY_MIN_RUNS && flip_rate >= FLAKY_FLIP_RATE),
+        retried_commits,
+        flip_rate,
+        flakiness_score: retried_share.max(flip_rate),
+    }
+}
+
+/// Nearest-rank percentile of the given durations.
+fn percentile(durations: &[u64], percent: usize) -> Option<u64> {
+    let mut sorted = durations.to_vec();
+    sorted.sort_unstable();
+    let rank = (sorted.len() * percent).div_ceil(100).max(1);
+    sorted.get(rank - 1).copied()
+}
+
+/// Pipeline status of a single branch as observed in a refresh.
+pub struct ObservedBranchPipeline {
+    pub repository: String,
+    pub branch: String,
+    pub status: PipelineStatus,
+    pub pipeline_url: Option<String>,
+    pub pipeline_timing: Option<PipelineTiming>,
+    pub pull_request_target_branch: bool,
+}
+
+/// Returns the pipeline status of every branch (PR target, PR source and standalone) once.
+pub fn get_branch_pipelines(data: &RepositoriesData) -> Vec<ObservedBranchPipeline> {
+    let mut observed_pipelines = Vec::new();
+    for repository in &data.repositories {
+        let mut seen_branches: HashSet<&str> = HashSet::new();
+        let target_branches = repository
+            .pull_request_target_branches
+            .iter()
+            .map(|branch| {
+                (
+                    &branch.branch_name,
+                    branch.pipeline_status,
+                    &branch.pipeline_url,
+                    &branch.pipeline_timing,
+                    true,
+                )
+            });
+        let source_branches = repository
+            .pull_request_target_branches
+            .iter()
+            .flat_map(|branch| &branch.pull_requests)
+            .map(|pr| {
+                (
+                    &pr.branch_name,
+                    pr.pipeline_status,
+                    &pr.pipeline_url,
+                    &pr.pipeline_timing,
+                    false,
+                )
+            });
+        let standalone_branches = repository.standalone_branches.iter().map(|branch| {
+            (
+                &branch.branch_name,
+                branch.pipeline_status,
+                &branch.pipeline_url,
+                &branch.pipeline_timing,
+                false,
+            )
+        });
+
+        for (branch, status, pipeline_url, pipeline_timing, pull_request_target_branch) in
+            target_branches
+                .chain(source_branches)
+                .chain(standalone_branches)
+        {
+            if seen_branches.insert(branch) {
+                observed_pipelines.push(ObservedBranchPipeline {
+                    repository: repository.repository_name.clone(),
+                    branch: branch.clone(),
+                    status,
+                    pipeline_url: pipeline_url.clone(),
+                    pipeline_timing: pipeline_timing.clone(),
+                    pull_request_target_branch,
+                });
+            }
+        }
+    }
+    observed_pipelines
+}
+
+#[cfg(test)]
+mod tests {
+    use migration::{Migrator, MigratorTrait};
+    use sea_orm::{ConnectOptions, Database};
+
+    use crate::adapter::db::pipelines::{
+        PipelineRunRepository, PipelineStatusTransitionRepository,
+    };
+    use crate::service::pipelines::pipeline_history_service::PipelineHistoryService;
+    use crate::service::repositories::model::{
+        PipelineStatus, PipelineTiming, RepositoriesData, RepositoryBranchData, StandaloneBranch,
+    };
+
+    async fn get_in_memory_service() -> PipelineHistoryService {
+        let connect_options = ConnectOptions::new("sqlite::memory:".to_owned());
+        let db_connection = Database::connect(connect_options).await.unwrap();
+        Migrator::up(&db_connection, None).await.unwrap();
+        PipelineHistoryService::new(
+            PipelineStatusTransitionRepository::new(db_connection.clone()),
+            PipelineRunRepository::new(db_connection),
+        )
+    }
+
+    fn get_data_with_branch_status(status: PipelineStatus) -> RepositoriesData {
+        RepositoriesData {
+            last_updated_date: None,
+            currently_refreshing: false,
+            stale: false,
+            repositories: vec![RepositoryBranchData {
+                repository_name: "repo1".to_string(),
+                repository_url: "url".to_string(),
+                pull_request_target_branches: vec![],
+                standalone_branches: vec![StandaloneBranch {
+                    branch_name: "main".to_string(),
+                    pipeline_status: status,
+                    pipeline_url: None,
+                    pipeline_jobs: vec![],
+                    pipeline_timing: None,
+                    last_commit: None,
+                    ahead_behind: None,
+                }],
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn record_transitions_only_status_changes() {
+        let service = get_in_memory_service().await;
+
+        for status in [
+            PipelineStatus::Successful,
+            PipelineStatus::Successful,
+            PipelineStatus::Failed,
+            PipelineStatus::Failed,
+            PipelineStatus::Successful,
+        ] {
+            service
+                .record_transitions(&get_data_with_branch_status(status))
+                .await
+                .unwrap();
+        }
+
+        let history = service.get_history("repo1", Some("main")).await.unwrap();
+
+        assert_eq!(1, history.len());
+        let statuses: Vec<PipelineStatus> = history[0]
+            .transitions
+            .iter()
+            .map(|transition| transition.status)
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                PipelineStatus::Successful,
+                PipelineStatus::Failed,
+                PipelineStatus::Successful
+            ]
+        );
+        assert_eq!(PipelineStatus::Successful, history[0].status);
+    }
+
+    fn get_data_with_finished_pipeline(
+        status: PipelineStatus,
+        commit_sha: &str,
+        minute: u32,
+        duration_seconds: u64,
+    ) -> RepositoriesData {
+        let mut data = get_data_with_branch_status(status);
+        data.repositories[0].standalone_branches[0].pipeline_timing = Some(PipelineTiming {
+            commit_sha: commit_sha.to_string(),
+            created_date: None,
+            started_date: None,
+            finished_date: Some(format!("2024-01-01T10:{minute:02}:00Z")),
+            duration_seconds: Some(duration_seconds),
+        });
+        data
+    }
+
+    #[tokio::test]
+    async fn get_statistics_of_successful_runs() {
+        let service = get_in_memory_service().await;
+
+        for (minute, status, duration_seconds) in [
+            (1, PipelineStatus::Successful, 100),
+            (2, PipelineStatus::Failed, 10),
+            (3, PipelineStatus::Successful, 300),
+            (4, PipelineStatus::Successful, 200),
+        ] {
+            let data = get_data_with_finished_pipeline(
+                status,
+                &format!("sha{minute}"),
+                minute,
+                duration_seconds,
+            );
+            // the same run is seen again on the next refresh
+            service.record_runs(&data).await.unwrap();
+            service.record_runs(&data).await.unwrap();
+        }
+
+        let statistics = service.get_statistics("repo1", None).await.unwrap();
+
+        assert_eq!(1, statistics.len());
+        assert_eq!(3, statistics[0].run_count);
+        assert_eq!(200, statistics[0].latest_duration_seconds);
+        assert_eq!(200, statistics[0].median_duration_seconds);
+        assert_eq!(300, statistics[0].p90_duration_seconds);
+        assert_eq!(None, statistics[0].previous_median_duration_seconds);
+    }
+
+    #[tokio::test]
+    async fn get_flakiness_of_retried_commit() {
+        let service = get_in_memory_service().await;
+
+        for (minute, commit_sha, status) in [
+            (1, "sha1", PipelineStatus::Successful),
+            (2, "sha2", PipelineStatus::Failed),
+            (3, "sha2", PipelineStatus::Successful),
+            (4, "sha3", PipelineStatus::Successful),
+        ] {
+            let data = get_data_with_finished_pipeline(status, commit_sha, minute, 100);
+            service.record_runs(&data).await.unwrap();
+        }
+
+        let flakiness = service.get_flakiness("repo1", Some("main")).await.unwrap();
+
+        assert_eq!(1, flakiness.len());
+        assert!(flakiness[0].flaky);
+        assert_eq!(vec!["sha2".to_string()], flakiness[0].retried_commits);
+        assert_eq!(4, flakiness[0].run_count);
+        // two of three consecutive runs changed the outcome
+        assert!((flakiness[0].flip_rate - 2.0 / 3.0).abs() < f64::EPSILON);
+    }
+}
@@ -15,7 +15,7 @@ pub enum PullRequestEventType {
     SourceBranchUpdated,
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize)]
 pub struct PullRequestEvent {
     pub id: Option<i32>,
     pub pr_id: String,
@@ -1,4 +1,5 @@
 use crate::adapter::db::prupdates::PullRequestEventRepository;
+use crate::service::events::{DashboardEvent, DashboardEventPublisher};
 use crate::service::prupdates::aggregate::aggregate_events;
 use crate::service::prupdates::model::{PullRequestEvent, PullRequestUpdate};
 use anyhow::Context;
@@ -11,17 +12,25 @@ const EVENT_MAX_AGE_DAYS: i64 = 7;
 #[derive(Clone)]
 pub struct PullRequestUpdateService {
     pr_event_repository: PullRequestEventRepository,
+    event_publisher: DashboardEventPublisher,
 }
 
 impl PullRequestUpdateService {
-    pub fn new(pr_event_repository: PullRequestEventRepository) -> Self {
+    pub fn new(
+        pr_event_repository: PullRequestEventRepository,
+        event_publisher: DashboardEventPublisher,
+    ) -> Self {
         Self {
             pr_event_repository,
+            event_publisher,
         }
     }
 
     pub async fn save_pr_event(&self, event: PullRequestEvent) -> anyhow::Result<()> {
-        self.pr_event_repository.save_event(event).await
+        self.pr_event_repository.save_event(event.clone()).await?;
+        self.event_publisher
+            .publish(DashboardEvent::PullRequest(event));
+        Ok(())
     }
 
     pub async fn get_pr_updates(
@@ -30,32 +39,26 @@ impl PullRequestUpdateService {
     ) -> anyhow::Result<Vec<PullRequestUpdate>> {
         let events = self.pr_event_repository.get_events().await?;
 
-        let mut grouped_events: HashMap<String, Vec<PullRequestEvent>> = HashMap::new();
-        events
-            .into_iter()
-            .filter(|event| match last_seen_timestamps.get(&event.pr_id) {
+        aggregate_into_updates(events.into_iter().filter(|event| {
+            match last_seen_timestamps.get(&event.pr_id) {
                 Some(timestamp) => event.timestamp.gt(timestamp),
                 None => true,
-            })
-            .for_each(|event| {
-                grouped_events
-                    .entry(event.pr_id.clone())
-                    .or_default()
-                    .push(event)
-            });
-
-        // sort by pr_id to achieve a stable order of pr updates
-        let mut map_entries: Vec<(String, Vec<PullRequestEvent>)> =
-            grouped_events.into_iter().collect();
-        map_entries.sort_by_key(|(pr_id, _evts)| pr_id.clone());
-
-        let updates = map_entries
-            .into_iter()
-            .map(|(pr_id, evts)| aggregate_events(pr_id, evts))
-            .collect::<anyhow::Result<Vec<PullRequestUpdate>>>()
-            .context("Could not aggregate events into update.")?;
+            }
+        }))
+    }
+
+    /// Returns the updates aggregated from all events newer than the given timestamp.
+    pub async fn get_pr_updates_since(
+        &self,
+        since: DateTime<Utc>,
+    ) -> anyhow::Result<Vec<PullRequestUpdate>> {
+        let events = self.pr_event_repository.get_events().await?;
 
-        Ok(updates)
+        aggregate_into_updates(
+            events
+                .into_iter()
+                .filter(|event| event.timestamp.gt(&since)),
+        )
     }
 
     pub async fn clean_up_pr_updates(&self) -> anyhow::Result<()> {
@@ -80,9 +83,35 @@ impl PullRequestUpdateService {
     }
 }
 
+fn aggregate_into_updates(
+    events: impl Iterator<Item = PullRequestEvent>,
+) -> anyhow::Result<Vec<PullRequestUpdate>> {
+    let mut grouped_events: HashMap<String, Vec<PullRequestEvent>> = HashMap::new();
+    events.for_each(|event| {
+        grouped_events
+            .entry(event.pr_id.clone())
+            .or_default()
+            .push(event)
+    });
+
+    // sort by pr_id to achieve a stable order of pr updates
+    let mut map_entries: Vec<(String, Vec<PullRequestEvent>)> =
+        grouped_events.into_iter().collect();
+    map_entries.sort_by_key(|(pr_id, _evts)| pr_id.clone());
+
+    let updates = map_entries
+        .into_iter()
+        .map(|(pr_id, evts)| aggregate_events(pr_id, evts))
+        .collect::<anyhow::Result<Vec<PullRequestUpdate>>>()
+        .context("Could not aggregate events into update.")?;
+
+    Ok(updates)
+}
+
 #[cfg(test)]
 mod tests {
     use crate::adapter::db::prupdates::PullRequestEventRepository;
+    use crate::service::events::DashboardEventPublisher;
     use crate::service::prupdates::model::{
         PullRequestEvent, PullRequestEventType, PullRequestTimestamp,
     };
@@ -118,7 +147,7 @@ mod tests {
     #[tokio::test]
     async fn clean_up_pr_updates_everything_new() {
         let repository = get_in_memory_repository().await;
-        let service = PullRequestUpdateService::new(repository);
+        let service = PullRequestUpdateService::new(repository, DashboardEventPublisher::new());
         service
             .save_pr_event(get_pr_event_with_timestamp(
                 "id1",
@@ -146,7 +175,7 @@ mod tests {
     #[tokio::test]
     async fn clean_up_pr_updates_cleaned_up() {
         let repository = get_in_memory_repository().await;
-        let service = PullRequestUpdateService::new(repository);
+        let service = PullRequestUpdateService::new(repository, DashboardEventPublisher::new());
         service
             .save_pr_event(get_pr_event_with_timestamp(
                 "id1",
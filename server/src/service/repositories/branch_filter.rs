@@ -0,0 +1,283 @@
+use anyhow::{anyhow, Context};
+use chrono::{DateTime, Duration, Utc};
+use regex::Regex;
+
+use crate::config::{BranchFilterConfiguration, BranchFiltersConfiguration};
+use crate::service::repositories::model::{repository_name_matches, RepositoriesData};
+
+#[derive(Clone)]
+enum BranchPattern {
+    Glob(String),
+    Regex(Regex),
+}
+
+impl BranchPattern {
+    fn parse(pattern: &str) -> anyhow::Result<Self> {
+        match pattern
+            .strip_prefix('/')
+            .and_then(|pattern| pattern.strip_suffix('/'))
+        {
+            Some(expression) => Regex::new(expression)
+                .map(BranchPattern::Regex)
+                .with_context(|| format!("Invalid branch pattern {pattern}.")),
+            None => Ok(BranchPattern::Glob(pattern.to_string())),
+        }
+    }
+
+    fn matches(&self, branch_name: &str) -> bool {
+        match self {
+            BranchPattern::Glob(pattern) => branch_name_matches(pattern, branch_name),
+            BranchPattern::Regex(regex) => regex.is_match(branch_name),
+        }
+    }
+}
+
+/// Decides which branches that are not part of a PR are shown for a repository.
+#[derive(Clone)]
+pub struct BranchFilter {
+    include: Vec<BranchPattern>,
+    exclude: Vec<BranchPattern>,
+    max_age: Option<Duration>,
+}
+
+impl BranchFilter {
+    pub fn new(configuration: &BranchFilterConfiguration) -> anyhow::Result<Self> {
+        let parse_patterns = |patterns: &[String]| {
+            patterns
+                .iter()
+                .map(|pattern| BranchPattern::parse(pattern))
+                .collect::<anyhow::Result<Vec<BranchPattern>>>()
+        };
+        if configuration.max_age_days.is_some_and(|days| days < 0) {
+            return Err(anyhow!("Maximum branch age must not be negative."));
+        }
+        Ok(Self {
+            include: parse_patterns(&configuration.include)?,
+            exclude: parse_patterns(&configuration.exclude)?,
+            max_age: configuration.max_age_days.map(Duration::days),
+        })
+    }
+
+    /// Whether the branch is shown. Branches whose last commit date is unknown are not hidden by
+    /// their age.
+    pub fn is_shown(&self, branch_name: &str, last_commit_date: Option<DateTime<Utc>>) -> bool {
+        let included = self.include.is_empty()
+            || self
+                .include
+                .iter()
+                .any(|pattern| pattern.matches(branch_name));
+        let excluded = self
+            .exclude
+            .iter()
+            .any(|pattern| pattern.matches(branch_name));
+        let outdated = match (self.max_age, last_commit_date) {
+            (Some(max_age), Some(date)) => date < Utc::now() - max_age,
+            _ => false,
+        };
+        included && !excluded && !outdated
+    }
+}
+
+/// The branch filters of all repositories.
+#[derive(Clone)]
+pub struct BranchFilters {
+    default: BranchFilter,
+    repositories: Vec<(String, BranchFilter)>,
+}
+
+impl BranchFilters {
+    pub fn new(configuration: &BranchFiltersConfiguration) -> anyhow::Result<Self> {
+        let repositories = configuration
+            .repositories
+            .iter()
+            .map(|repository_filter| {
+                BranchFilter::new(&repository_filter.filter)
+                    .map(|filter| (repository_filter.repository.clone(), filter))
+                    .with_context(|| {
+                        format!(
+                            "Invalid branch filter for repository {}.",
+                            repository_filter.repository
+                        )
+                    })
+            })
+            .collect::<anyhow::Result<Vec<(String, BranchFilter)>>>()?;
+        Ok(Self {
+            default: BranchFilter::new(&configuration.default)
+                .context("Invalid default branch filter.")?,
+            repositories,
+        })
+    }
+
+    pub fn get_filter(&self, repository_name: &str) -> &BranchFilter {
+        self.repositories
+            .iter()
+            .find(|(configured, _)| repository_name_matches(configured, repository_name))
+            .map(|(_, filter)| filter)
+            .unwrap_or(&self.default)
+    }
+
+    /// Removes the standalone branches hidden by the filter of their repository. Branches of PRs
+    /// are always shown.
+    pub fn apply(&self, data: &mut RepositoriesData) {
+        for repository in &mut data.repositories {
+            let filter = self.get_filter(&repository.repository_name);
+            repository.standalone_branches.retain(|branch| {
+                let last_commit_date = branch
+                    .last_commit
+                    .as_ref()
+                    .and_then(|commit| parse_commit_date(&commit.date));
+                filter.is_shown(&branch.branch_name, last_commit_date)
+            });
+        }
+    }
+}
+
+/// Parses the RFC 3339 date of a commit, as returned by GitLab and stored in [`Commit`].
+///
+/// [`Commit`]: crate::service::repositories::model::Commit
+pub fn parse_commit_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(date)
+        .ok()
+        .map(|date| date.with_timezone(&Utc))
+}
+
+/// Matches a branch name against a pattern in which `*` stands for any (also empty) characters.
+pub fn branch_name_matches(pattern: &str, branch_name: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = branch_name.strip_prefix(first) else {
+        return false;
+    };
+    let remaining_parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = remaining_parts.split_last() else {
+        // no wildcard in the pattern
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::{Duration, Utc};
+
+    use crate::config::{
+        BranchFilterConfiguration, BranchFiltersConfiguration, RepositoryBranchFilterConfiguration,
+    };
+    use crate::service::repositories::branch_filter::{
+        branch_name_matches, BranchFilter, BranchFilters,
+    };
+    use crate::service::repositories::model::{
+        Commit, PipelineStatus, RepositoriesData, RepositoryBranchData, StandaloneBranch,
+    };
+
+    #[test]
+    fn branch_name_matches_wildcards() {
+        assert!(branch_name_matches("main", "main"));
+        assert!(!branch_name_matches("main", "main-old"));
+        assert!(branch_name_matches("release/*", "release/1.0"));
+        assert!(!branch_name_matches("release/*", "feature/release/1.0"));
+        assert!(branch_name_matches("*/team-a/*", "feature/team-a/login"));
+        assert!(branch_name_matches("*", "anything"));
+        assert!(!branch_name_matches("a*b*a", "ab"));
+    }
+
+    #[test]
+    fn is_shown_applies_include_exclude_and_age() {
+        let filter = BranchFilter::new(&BranchFilterConfiguration {
+            include: vec!["main".to_string(), "/^release-\\d+$/".to_string()],
+            exclude: vec!["release-0".to_string()],
+            max_age_days: Some(30),
+        })
+        .unwrap();
+        let recent = Some(Utc::now() - Duration::days(1));
+        let old = Some(Utc::now() - Duration::days(31));
+
+        assert!(filter.is_shown("main", recent));
+        assert!(filter.is_shown("main", None));
+        assert!(filter.is_shown("release-12", recent));
+        assert!(!filter.is_shown("release-12", old));
+        assert!(!filter.is_shown("release-0", recent));
+        assert!(!filter.is_shown("release-x", recent));
+        assert!(!filter.is_shown("feature-1", recent));
+    }
+
+    #[test]
+    fn new_rejects_invalid_regex() {
+        let configuration = BranchFilterConfiguration {
+            include: vec!["/[/".to_string()],
+            exclude: vec![],
+            max_age_days: None,
+        };
+
+        assert!(BranchFilter::new(&configuration).is_err());
+    }
+
+    #[test]
+    fn apply_filters_standalone_branches_of_repository() {
+        let filters = BranchFilters::new(&BranchFiltersConfiguration {
+            default: BranchFilterConfiguration::default(),
+            repositories: vec![RepositoryBranchFilterConfiguration {
+                repository: "group/repo1".to_string(),
+                filter: BranchFilterConfiguration {
+                    include: vec![],
+                    exclude: vec!["wip-*".to_string()],
+                    max_age_days: Some(30),
+                },
+            }],
+        })
+        .unwrap();
+        let get_branch = |branch_name: &str, days_ago: i64| StandaloneBranch {
+            branch_name: branch_name.to_string(),
+            pipeline_status: PipelineStatus::None,
+            pipeline_url: None,
+            pipeline_jobs: vec![],
+            pipeline_timing: None,
+            last_commit: Some(Commit {
+                sha: "sha".to_string(),
+                author: "author".to_string(),
+                message_headline: "message".to_string(),
+                date: (Utc::now() - Duration::days(days_ago))
+                    .format("%+")
+                    .to_string(),
+            }),
+            ahead_behind: None,
+        };
+        let get_repository = |repository_name: &str| RepositoryBranchData {
+            repository_name: repository_name.to_string(),
+            repository_url: "url".to_string(),
+            pull_request_target_branches: vec![],
+            standalone_branches: vec![
+                get_branch("feature-1", 1),
+                get_branch("wip-1", 1),
+                get_branch("feature-2", 60),
+            ],
+        };
+        let mut data = RepositoriesData {
+            last_updated_date: None,
+            currently_refreshing: false,
+            stale: false,
+            repositories: vec![get_repository("group/repo1"), get_repository("group/repo2")],
+        };
+
+        filters.apply(&mut data);
+
+        let branch_names = |repository: &RepositoryBranchData| -> Vec<String> {
+            repository
+                .standalone_branches
+                .iter()
+                .map(|branch| branch.branch_name.clone())
+                .collect()
+        };
+        assert_eq!(vec!["feature-1"], branch_names(&data.repositories[0]));
+        assert_eq!(
+            vec!["feature-1", "wip-1", "feature-2"],
+            branch_names(&data.repositories[1])
+        );
+    }
+}
@@ -1,9 +1,11 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
 use std::time::Duration;
 
 use log::debug;
 use tokio::time::Instant;
 
-use crate::service::repositories::model::RepositoriesData;
+use crate::service::repositories::model::{AheadBehind, RepositoriesData};
 
 pub struct RepositoriesDataCache {
     repositories_data: RepositoriesData,
@@ -16,6 +18,7 @@ impl RepositoriesDataCache {
             last_updated_date: None,
             repositories: vec![],
             currently_refreshing: false,
+            stale: false,
         };
 
         Self {
@@ -29,6 +32,16 @@ impl RepositoriesDataCache {
         self.last_load_instant = Some(Instant::now());
     }
 
+    /// Serves previously persisted data until the first load has finished. Unlike `cache_data` this
+    /// does not count as a load, so the data will be reloaded on the next request.
+    pub fn restore_data(&mut self, data: RepositoriesData) {
+        self.repositories_data = RepositoriesData {
+            stale: true,
+            currently_refreshing: false,
+            ..data
+        };
+    }
+
     pub fn get_cached_data(&mut self) -> RepositoriesData {
         self.repositories_data.clone()
     }
@@ -57,3 +70,70 @@ impl RepositoriesDataCache {
         }
     }
 }
+
+/// Responses of the VCS server which do not change anymore once they are known, like the
+/// ahead/behind counts of two commits. They are kept across refreshes instead of being loaded
+/// again, while the ones which were not needed by the last refresh are dropped.
+pub struct RefreshCache<K, V> {
+    values: HashMap<K, V>,
+    used: HashSet<K>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> RefreshCache<K, V> {
+    pub fn get(&mut self, key: &K) -> Option<V> {
+        let value = self.values.get(key).cloned();
+        if value.is_some() {
+            self.used.insert(key.clone());
+        }
+        value
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        self.used.insert(key.clone());
+        self.values.insert(key, value);
+    }
+
+    /// Ends a refresh, dropping the values it did not use.
+    pub fn finish_refresh(&mut self) {
+        let used = std::mem::take(&mut self.used);
+        self.values.retain(|key, _| used.contains(key));
+    }
+}
+
+impl<K, V> Default for RefreshCache<K, V> {
+    fn default() -> Self {
+        Self {
+            values: HashMap::new(),
+            used: HashSet::new(),
+        }
+    }
+}
+
+/// Ahead/behind counts by source and target commit.
+pub type AheadBehindCache = RefreshCache<(String, String), AheadBehind>;
+
+#[cfg(test)]
+mod tests {
+    use crate::service::repositories::cache::AheadBehindCache;
+    use crate::service::repositories::model::AheadBehind;
+
+    #[test]
+    fn refresh_cache_drops_unused_values() {
+        let ahead_behind = AheadBehind {
+            ahead: 2,
+            behind: 5,
+        };
+        let a = ("a1".to_string(), "main1".to_string());
+        let b = ("b1".to_string(), "main1".to_string());
+        let mut cache = AheadBehindCache::default();
+        cache.insert(a.clone(), ahead_behind);
+        cache.insert(b.clone(), ahead_behind);
+        cache.finish_refresh();
+
+        assert_eq!(Some(ahead_behind), cache.get(&a));
+        cache.finish_refresh();
+
+        assert_eq!(Some(ahead_behind), cache.get(&a));
+        assert_eq!(None, cache.get(&b));
+    }
+}
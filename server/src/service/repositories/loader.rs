@@ -2,22 +2,26 @@ use anyhow::{anyhow, Context};
 
 use crate::adapter::bitbucket::repositories::BitbucketClient;
 use crate::adapter::gitlab::repositories::GitlabClient;
-use crate::service::repositories::model::{RepositoriesData, Repository};
+use crate::service::repositories::branch_filter::BranchFilters;
+use crate::service::repositories::model::{RepositoriesData, RepositorySelector};
 use crate::Configuration;
 
 pub struct DataLoader {
     gitlab_client: Option<GitlabClient>,
     bitbucket_client: Option<BitbucketClient>,
+    branch_filters: BranchFilters,
 }
 
 impl DataLoader {
     pub fn new(configuration: &Configuration) -> anyhow::Result<Self> {
         let repositories = configuration
-            .repositories
+            .all_repositories()
             .iter()
-            .map(|repo| Repository::from_slug(repo))
-            .collect::<anyhow::Result<Vec<Repository>>>()
+            .map(|repo| RepositorySelector::parse(repo))
+            .collect::<anyhow::Result<Vec<RepositorySelector>>>()
             .context("Could not parse repositories from configuration.")?;
+        let branch_filters = BranchFilters::new(&configuration.branch_filters)
+            .context("Could not parse branch filters from configuration.")?;
 
         let mut gitlab_client = None;
         if let Some(gitlab_config) = &configuration.gitlab {
@@ -25,6 +29,8 @@ impl DataLoader {
                 &repositories,
                 gitlab_config.url.clone(),
                 gitlab_config.token.clone(),
+                configuration.repository_discovery.clone(),
+                branch_filters.clone(),
             ));
         }
 
@@ -36,6 +42,8 @@ impl DataLoader {
                     bitbucket_config.url.clone(),
                     bitbucket_config.user.clone(),
                     bitbucket_config.password.clone(),
+                    configuration.repository_discovery.clone(),
+                    branch_filters.clone(),
                 )
                 .context("Could not create bitbucket client.")?,
             );
@@ -48,22 +56,27 @@ impl DataLoader {
         Ok(Self {
             gitlab_client,
             bitbucket_client,
+            branch_filters,
         })
     }
 
     pub async fn load_data(&self) -> anyhow::Result<RepositoriesData> {
         if let Some(gitlab_client) = &self.gitlab_client {
-            let data = gitlab_client
+            let mut data = gitlab_client
                 .load_repositories_data()
                 .await
                 .context("Could not load dashboard data from Gitlab.")?;
+            // the clients already skip hidden branches, this only catches ones they could not date
+            self.branch_filters.apply(&mut data);
             return Ok(data);
         }
         if let Some(bitbucket_client) = &self.bitbucket_client {
-            let data = bitbucket_client
+            let mut data = bitbucket_client
                 .load_repositories_data()
                 .await
                 .context("Could not load dashboard data from Bitbucket.")?;
+            // the clients already skip hidden branches, this only catches ones they could not date
+            self.branch_filters.apply(&mut data);
             return Ok(data);
         }
         Err(anyhow!(
@@ -3,19 +3,56 @@ use std::sync::Arc;
 use log::{error, info, warn};
 use tokio::sync::mpsc::UnboundedReceiver;
 
+use crate::adapter::db::snapshots::RepositorySnapshotRepository;
+use crate::service::alerts::alert_service::AlertService;
+use crate::service::events::{DashboardEvent, DashboardEventPublisher};
+use crate::service::pipelines::pipeline_history_service::PipelineHistoryService;
 use crate::service::repositories::cache::RepositoriesDataCache;
 use crate::service::repositories::loader::DataLoader;
+use crate::service::repositories::model::RepositoriesData;
 
+pub mod branch_filter;
 pub mod cache;
 pub mod loader;
 pub mod model;
+pub mod user_pull_requests;
 
 pub type LockableCache = Arc<tokio::sync::Mutex<RepositoriesDataCache>>;
 
+/// Everything that needs to happen with freshly loaded data besides caching it.
+pub struct LoadedDataHandlers {
+    pub snapshot_repository: RepositorySnapshotRepository,
+    pub pipeline_history_service: PipelineHistoryService,
+    pub alert_service: AlertService,
+    pub event_publisher: DashboardEventPublisher,
+}
+
+impl LoadedDataHandlers {
+    async fn handle(&self, data: &RepositoriesData) {
+        if let Err(err) = self.snapshot_repository.save_snapshot(data).await {
+            error!("Could not persist dashboard data snapshot: {:#}", err);
+        }
+        match self.pipeline_history_service.record_transitions(data).await {
+            Ok(changes) => changes.into_iter().for_each(|change| {
+                self.event_publisher
+                    .publish(DashboardEvent::PipelineStatusChanged(change))
+            }),
+            Err(err) => error!("Could not record pipeline status history: {:#}", err),
+        }
+        if let Err(err) = self.pipeline_history_service.record_runs(data).await {
+            error!("Could not record pipeline runs: {:#}", err);
+        }
+        if let Err(err) = self.alert_service.update_alerts(data).await {
+            error!("Could not update alerts: {:#}", err);
+        }
+    }
+}
+
 pub async fn keep_loading_repositories_data(
     mut reload_receiver: UnboundedReceiver<()>,
     cache: LockableCache,
     data_loader: DataLoader,
+    handlers: LoadedDataHandlers,
 ) {
     loop {
         match reload_receiver.recv().await {
@@ -31,9 +68,11 @@ pub async fn keep_loading_repositories_data(
                     match data_loader.load_data().await {
                         Ok(data) => {
                             let mut locked_cache = cache.lock().await;
-                            locked_cache.cache_data(data);
+                            locked_cache.cache_data(data.clone());
                             locked_cache.set_refreshing(false);
                             drop(locked_cache);
+                            // serve the fresh data before the history and alerts are updated
+                            handlers.handle(&data).await;
                         }
                         Err(err) => {
                             error!("Could not reload dashboard data: {:#}", err);
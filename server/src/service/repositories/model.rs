@@ -1,16 +1,18 @@
 use std::fmt::{Display, Formatter};
 
 use anyhow::anyhow;
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct RepositoriesData {
     pub last_updated_date: Option<String>,
     pub currently_refreshing: bool,
+    /// Set while the data comes from the snapshot persisted before the last restart.
+    pub stale: bool,
     pub repositories: Vec<RepositoryBranchData>,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct RepositoryBranchData {
     pub repository_name: String,
     pub repository_url: String,
@@ -18,70 +20,479 @@ pub struct RepositoryBranchData {
     pub standalone_branches: Vec<StandaloneBranch>,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct PullRequestTargetBranch {
     pub branch_name: String,
     pub pipeline_url: Option<String>,
     pub pipeline_status: PipelineStatus,
+    #[serde(default)]
+    pub pipeline_jobs: Vec<PipelineJob>,
+    #[serde(default)]
+    pub pipeline_timing: Option<PipelineTiming>,
+    #[serde(default)]
+    pub last_commit: Option<Commit>,
     pub pull_requests: Vec<PullRequest>,
 }
 
-#[derive(Serialize, Clone)]
+#[derive(Serialize, Deserialize, Clone)]
 pub struct PullRequest {
+    /// Number of the PR within its repository (Bitbucket ID, GitLab IID).
+    #[serde(default)]
+    pub number: u32,
+    #[serde(default)]
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// Set for draft and work in progress PRs.
+    #[serde(default)]
+    pub draft: bool,
     pub branch_name: String,
     pub user_name: String,
+    /// Login of the author on the git server (Bitbucket slug, GitLab username).
+    #[serde(default)]
+    pub user_id: String,
     pub user_profile_image: String,
     pub comment_count: u32,
+    #[serde(default)]
+    pub created_date: String,
     pub last_activity_date: String,
-    pub approved: bool,
+    #[serde(default)]
+    pub review: ReviewSummary,
+    #[serde(default)]
+    pub reviewers: Vec<Reviewer>,
+    /// Commits relative to the target branch.
+    #[serde(default)]
+    pub ahead_behind: Option<AheadBehind>,
+    /// Not set while the git server is still checking whether the PR can be merged.
+    #[serde(default)]
+    pub mergeability: Option<Mergeability>,
     pub pipeline_status: PipelineStatus,
     pub pipeline_url: Option<String>,
+    #[serde(default)]
+    pub pipeline_jobs: Vec<PipelineJob>,
+    #[serde(default)]
+    pub pipeline_timing: Option<PipelineTiming>,
     pub link_url: String,
 }
 
+/// Whether a PR can be merged and what prevents it otherwise.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Mergeability {
+    pub can_merge: bool,
+    pub has_conflicts: bool,
+    pub blockers: Vec<MergeBlocker>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct MergeBlocker {
+    pub kind: MergeBlockerKind,
+    /// Explanation of the blocker as reported by the git server.
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MergeBlockerKind {
+    Conflicts,
+    Approvals,
+    UnresolvedThreads,
+    Pipeline,
+    Draft,
+    /// Anything else, like merge checks of plugins or branch restrictions.
+    Other,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Reviewer {
+    /// Login of the reviewer on the git server (Bitbucket slug, GitLab username).
+    pub user_id: String,
+    pub user_name: String,
+    pub approved: bool,
+    /// Set if the reviewer requested changes, which Bitbucket calls "needs work".
+    #[serde(default)]
+    pub changes_requested: bool,
+}
+
+/// Summary of the reviews of a PR.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ReviewSummary {
+    /// Set if the PR has the approvals it needs and nobody requested changes.
+    pub approved: bool,
+    pub approvals: u32,
+    /// Not set if the repository does not require a number of approvals.
+    pub approvals_required: Option<u32>,
+    /// User IDs of the reviewers who requested changes.
+    pub changes_requested_by: Vec<String>,
+    /// User IDs of the reviewers who have neither approved nor requested changes.
+    pub pending_reviewers: Vec<String>,
+}
+
+impl ReviewSummary {
+    /// Summarizes the reviews, where a PR without required approvals needs a single one.
+    pub fn new(reviewers: &[Reviewer], approvals_required: Option<u32>) -> Self {
+        let approvals = reviewers
+            .iter()
+            .filter(|reviewer| reviewer.approved)
+            .count() as u32;
+        let changes_requested_by: Vec<String> = reviewers
+            .iter()
+            .filter(|reviewer| reviewer.changes_requested)
+            .map(|reviewer| reviewer.user_id.to_owned())
+            .collect();
+        let pending_reviewers = reviewers
+            .iter()
+            .filter(|reviewer| !reviewer.approved && !reviewer.changes_requested)
+            .map(|reviewer| reviewer.user_id.to_owned())
+            .collect();
+
+        Self {
+            approved: approvals >= approvals_required.unwrap_or(1)
+                && changes_requested_by.is_empty(),
+            approvals,
+            approvals_required,
+            changes_requested_by,
+            pending_reviewers,
+        }
+    }
+}
+
+/// A pull request together with where it is located, as listed in the per-user views.
+#[derive(Serialize, Clone)]
+pub struct UserPullRequest {
+    pub repository_name: String,
+    pub target_branch: String,
+    pub pull_request: PullRequest,
+}
+
 #[derive(Serialize, Clone)]
+pub struct UserPullRequests {
+    pub user: String,
+    /// PRs opened by the user.
+    pub authored: Vec<UserPullRequest>,
+    /// PRs the user is a reviewer of and has not approved yet.
+    pub awaiting_review: Vec<UserPullRequest>,
+    /// PRs the user is a reviewer of and has approved.
+    pub reviewed: Vec<UserPullRequest>,
+}
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct StandaloneBranch {
     pub branch_name: String,
     pub pipeline_status: PipelineStatus,
     pub pipeline_url: Option<String>,
+    #[serde(default)]
+    pub pipeline_jobs: Vec<PipelineJob>,
+    #[serde(default)]
+    pub pipeline_timing: Option<PipelineTiming>,
+    #[serde(default)]
+    pub last_commit: Option<Commit>,
+    /// Commits relative to the default branch of the repository.
+    #[serde(default)]
+    pub ahead_behind: Option<AheadBehind>,
 }
 
-#[derive(Serialize, Clone)]
+/// How many commits a branch has that its base does not have and vice versa.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct AheadBehind {
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+/// A job of a pipeline, or on Bitbucket one of the builds reported for a commit. GitLab jobs are
+/// only loaded for pipelines which failed, are still running or wait for a manual job.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PipelineJob {
+    pub name: String,
+    /// Only known on GitLab.
+    pub stage: Option<String>,
+    pub status: PipelineStatus,
+    pub duration_seconds: Option<u64>,
+    pub url: Option<String>,
+}
+
+/// When the latest pipeline of a branch ran. On Bitbucket it spans all builds of the commit, and
+/// only what the builds report about their duration is known.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct PipelineTiming {
+    /// Commit the pipeline ran for.
+    pub commit_sha: String,
+    pub created_date: Option<String>,
+    pub started_date: Option<String>,
+    pub finished_date: Option<String>,
+    /// Not set until the pipeline has finished.
+    pub duration_seconds: Option<u64>,
+}
+
+/// The latest commit of a branch.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct Commit {
+    pub sha: String,
+    pub author: String,
+    /// First line of the commit message.
+    pub message_headline: String,
+    pub date: String,
+}
+
+impl Commit {
+    pub fn get_message_headline(message: &str) -> String {
+        message
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    }
+}
+
+#[derive(
+    Serialize,
+    Deserialize,
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    strum::Display,
+    strum::EnumString,
+)]
 pub enum PipelineStatus {
     Running,
     Successful,
     Failed,
     Queued,
     Canceled,
+    /// Waiting for a manual job which is allowed to be skipped.
+    Manual,
+    /// Waiting for a manual job which has to run before the pipeline can continue.
+    Blocked,
+    /// Waiting for a delayed job.
+    Scheduled,
+    Skipped,
     None,
 }
 
+impl PipelineStatus {
+    /// Combines the statuses of several builds of the same commit, where the most severe one wins.
+    pub fn combine(statuses: impl IntoIterator<Item = PipelineStatus>) -> PipelineStatus {
+        statuses
+            .into_iter()
+            .max_by_key(|status| match status {
+                PipelineStatus::None => 0,
+                PipelineStatus::Skipped => 1,
+                PipelineStatus::Successful => 2,
+                PipelineStatus::Canceled => 3,
+                PipelineStatus::Scheduled => 4,
+                PipelineStatus::Queued => 5,
+                PipelineStatus::Manual => 6,
+                PipelineStatus::Running => 7,
+                PipelineStatus::Blocked => 8,
+                PipelineStatus::Failed => 9,
+            })
+            .unwrap_or(PipelineStatus::None)
+    }
+
+    /// Whether the jobs of a pipeline are of interest, as they tell what failed, is running or
+    /// waits for someone to start it.
+    pub fn has_relevant_jobs(&self) -> bool {
+        matches!(
+            self,
+            PipelineStatus::Running
+                | PipelineStatus::Failed
+                | PipelineStatus::Manual
+                | PipelineStatus::Blocked
+        )
+    }
+}
+
+/// Checks whether a configured repository slug like `PROJECT/repo` refers to the repository with
+/// the given slug. Namespace wildcards match the repositories directly in the namespace (`group/*`)
+/// or also the ones in its subgroups (`group/**`). Both servers treat slugs case-insensitively.
+pub fn repository_name_matches(configured: &str, repository_name: &str) -> bool {
+    let configured = configured.to_lowercase();
+    let repository_name = repository_name.to_lowercase();
+    match RepositorySelector::parse(&configured) {
+        Ok(RepositorySelector::Namespace {
+            namespace,
+            include_subgroups,
+        }) => repository_name
+            .strip_prefix(&format!("{namespace}/"))
+            .is_some_and(|path| include_subgroups || !path.contains('/')),
+        _ => configured == repository_name,
+    }
+}
+
+/// Checks whether a repository is part of a configured selection, where an empty selection
+/// includes every repository.
+pub fn is_repository_selected(selection: &[String], repository_name: &str) -> bool {
+    selection.is_empty()
+        || selection
+            .iter()
+            .any(|configured| repository_name_matches(configured, repository_name))
+}
+
 #[derive(Clone)]
 pub struct Repository {
     pub name: String,
-    pub group: String,
+    /// Path of the namespace the repository is in: the Bitbucket project key or the full GitLab
+    /// group path including subgroups (`group/subgroup`).
+    pub namespace: String,
 }
 
 impl Repository {
+    /// Parses a slug like `PROJECT/repo` or `group/subgroup/repo`, where everything before the last
+    /// segment is the namespace.
     pub fn from_slug(slug: &str) -> anyhow::Result<Self> {
-        let parts: Vec<&str> = slug.split('/').collect();
-
-        let group = parts
-            .first()
-            .ok_or_else(|| anyhow!("Could not parse group name from {}.", slug))?;
-        let name = parts
-            .last()
-            .ok_or_else(|| anyhow!("Could not parse repository name from {}.", slug))?;
+        let (namespace, name) = slug
+            .rsplit_once('/')
+            .filter(|_| !slug.split('/').any(|segment| segment.is_empty()))
+            .ok_or_else(|| anyhow!("Could not parse repository slug {}.", slug))?;
 
         Ok(Self {
             name: name.to_string(),
-            group: group.to_string(),
+            namespace: namespace.to_string(),
         })
     }
 }
 
+/// A configured repository, or a namespace whose repositories are discovered at refresh time:
+/// `PROJECT/*` for the repositories of a Bitbucket project or GitLab group and `group/**` for the
+/// ones of a GitLab group including its subgroups.
+#[derive(Clone)]
+pub enum RepositorySelector {
+    Repository(Repository),
+    Namespace {
+        namespace: String,
+        include_subgroups: bool,
+    },
+}
+
+impl RepositorySelector {
+    pub fn parse(configured: &str) -> anyhow::Result<Self> {
+        if let Some(namespace) = configured.strip_suffix("/**") {
+            return Ok(RepositorySelector::Namespace {
+                namespace: namespace.to_string(),
+                include_subgroups: true,
+            });
+        }
+        if let Some(namespace) = configured.strip_suffix("/*") {
+            return Ok(RepositorySelector::Namespace {
+                namespace: namespace.to_string(),
+                include_subgroups: false,
+            });
+        }
+        Repository::from_slug(configured).map(RepositorySelector::Repository)
+    }
+}
+
+/// Collects the repositories selected directly and the ones discovered for namespaces, without
+/// duplicates.
+pub fn merge_repositories(
+    repositories: &mut Vec<Repository>,
+    discovered: impl IntoIterator<Item = Repository>,
+) {
+    for repository in discovered {
+        if !repositories
+            .iter()
+            .any(|existing| existing.to_string() == repository.to_string())
+        {
+            repositories.push(repository);
+        }
+    }
+}
+
 impl Display for Repository {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}/{}", self.group, self.name)
+        write!(f, "{}/{}", self.namespace, self.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::service::repositories::model::{
+        repository_name_matches, PipelineStatus, RepositorySelector, ReviewSummary, Reviewer,
+    };
+
+    #[test]
+    fn parse_repository_selector() {
+        let RepositorySelector::Repository(repository) =
+            RepositorySelector::parse("group/subgroup/repo").unwrap()
+        else {
+            panic!("Expected a single repository.");
+        };
+        assert_eq!("group/subgroup", repository.namespace);
+        assert_eq!("repo", repository.name);
+
+        assert!(matches!(
+            RepositorySelector::parse("group/**").unwrap(),
+            RepositorySelector::Namespace {
+                include_subgroups: true,
+                ..
+            }
+        ));
+        assert!(matches!(
+            RepositorySelector::parse("PROJECT/*").unwrap(),
+            RepositorySelector::Namespace {
+                include_subgroups: false,
+                ..
+            }
+        ));
+        assert!(RepositorySelector::parse("group//repo").is_err());
+        assert!(RepositorySelector::parse("repo").is_err());
+    }
+
+    #[test]
+    fn repository_name_matches_namespace() {
+        assert!(repository_name_matches("group/*", "group/repo"));
+        assert!(repository_name_matches("group/**", "group/subgroup/repo"));
+        assert!(!repository_name_matches("group/*", "other/repo"));
+        assert!(!repository_name_matches("group/*", "group/subgroup/repo"));
+        assert!(!repository_name_matches("group/**", "groupie/repo"));
+        assert!(repository_name_matches("PROJECT/*", "project/repo"));
+        assert!(!repository_name_matches("PROJECT/*", "OTHER/repo"));
+    }
+
+    #[test]
+    fn review_summary_requires_approvals() {
+        let reviewer = |user_id: &str, approved: bool, changes_requested: bool| Reviewer {
+            user_id: user_id.to_string(),
+            user_name: user_id.to_string(),
+            approved,
+            changes_requested,
+        };
+        let reviewers = vec![
+            reviewer("alice", true, false),
+            reviewer("bob", false, false),
+            reviewer("carol", false, true),
+        ];
+
+        let summary = ReviewSummary::new(&reviewers[..2], Some(2));
+        assert!(!summary.approved);
+        assert_eq!(1, summary.approvals);
+        assert_eq!(vec!["bob".to_string()], summary.pending_reviewers);
+
+        assert!(ReviewSummary::new(&reviewers[..2], None).approved);
+
+        let summary = ReviewSummary::new(&reviewers, None);
+        assert!(!summary.approved);
+        assert_eq!(vec!["carol".to_string()], summary.changes_requested_by);
+    }
+
+    #[test]
+    fn combine_pipeline_statuses() {
+        let combine = |statuses: &[PipelineStatus]| PipelineStatus::combine(statuses.to_vec());
+
+        assert_eq!(
+            PipelineStatus::Failed,
+            combine(&[
+                PipelineStatus::Successful,
+                PipelineStatus::Failed,
+                PipelineStatus::Running
+            ])
+        );
+        assert_eq!(
+            PipelineStatus::Running,
+            combine(&[PipelineStatus::Successful, PipelineStatus::Running])
+        );
+        assert_eq!(PipelineStatus::None, combine(&[]));
     }
 }
@@ -0,0 +1,136 @@
+use crate::service::repositories::model::{
+    PullRequest, RepositoriesData, UserPullRequest, UserPullRequests,
+};
+
+/// Collects the PRs a user authored or reviews. The user matches by login or display name,
+/// ignoring case, as the dashboard login does not necessarily use the same ids as the git server.
+pub fn get_user_pull_requests(data: &RepositoriesData, user: &str) -> UserPullRequests {
+    let matches_user = |user_id: &str, user_name: &str| {
+        user_id.eq_ignore_ascii_case(user) || user_name.eq_ignore_ascii_case(user)
+    };
+
+    let mut user_pull_requests = UserPullRequests {
+        user: user.to_string(),
+        authored: vec![],
+        awaiting_review: vec![],
+        reviewed: vec![],
+    };
+    for repository in &data.repositories {
+        for target_branch in &repository.pull_request_target_branches {
+            for pull_request in &target_branch.pull_requests {
+                let to_user_pull_request = |pull_request: &PullRequest| UserPullRequest {
+                    repository_name: repository.repository_name.clone(),
+                    target_branch: target_branch.branch_name.clone(),
+                    pull_request: pull_request.clone(),
+                };
+
+                if matches_user(&pull_request.user_id, &pull_request.user_name) {
+                    user_pull_requests
+                        .authored
+                        .push(to_user_pull_request(pull_request));
+                }
+                let review = pull_request
+                    .reviewers
+                    .iter()
+                    .find(|reviewer| matches_user(&reviewer.user_id, &reviewer.user_name));
+                match review {
+                    Some(reviewer) if reviewer.approved => user_pull_requests
+                        .reviewed
+                        .push(to_user_pull_request(pull_request)),
+                    Some(_) => user_pull_requests
+                        .awaiting_review
+                        .push(to_user_pull_request(pull_request)),
+                    None => {}
+                }
+            }
+        }
+    }
+    user_pull_requests
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::service::repositories::model::{
+        PipelineStatus, PullRequest, PullRequestTargetBranch, RepositoriesData,
+        RepositoryBranchData, ReviewSummary, Reviewer, UserPullRequest,
+    };
+    use crate::service::repositories::user_pull_requests::get_user_pull_requests;
+
+    fn get_pull_request(branch_name: &str, author: &str, reviewers: Vec<Reviewer>) -> PullRequest {
+        PullRequest {
+            number: 1,
+            title: branch_name.to_string(),
+            description: None,
+            draft: false,
+            branch_name: branch_name.to_string(),
+            user_name: format!("{author} Name"),
+            user_id: author.to_string(),
+            user_profile_image: "image".to_string(),
+            comment_count: 0,
+            created_date: "2024-01-01T00:00:00Z".to_string(),
+            last_activity_date: "2024-01-01T00:00:00Z".to_string(),
+            review: ReviewSummary::new(&reviewers, None),
+            mergeability: None,
+            reviewers,
+            ahead_behind: None,
+            pipeline_status: PipelineStatus::Successful,
+            pipeline_url: None,
+            pipeline_jobs: vec![],
+            pipeline_timing: None,
+            link_url: "link".to_string(),
+        }
+    }
+
+    fn branches(pull_requests: &[UserPullRequest]) -> Vec<&str> {
+        pull_requests
+            .iter()
+            .map(|pr| pr.pull_request.branch_name.as_str())
+            .collect()
+    }
+
+    fn get_reviewer(user_id: &str, approved: bool) -> Reviewer {
+        Reviewer {
+            user_id: user_id.to_string(),
+            user_name: format!("{user_id} Name"),
+            approved,
+            changes_requested: false,
+        }
+    }
+
+    #[test]
+    fn get_user_pull_requests_by_author_and_reviewer() {
+        let data = RepositoriesData {
+            last_updated_date: None,
+            currently_refreshing: false,
+            stale: false,
+            repositories: vec![RepositoryBranchData {
+                repository_name: "repo1".to_string(),
+                repository_url: "url".to_string(),
+                pull_request_target_branches: vec![PullRequestTargetBranch {
+                    branch_name: "main".to_string(),
+                    pipeline_url: None,
+                    pipeline_jobs: vec![],
+                    pipeline_timing: None,
+                    pipeline_status: PipelineStatus::Successful,
+                    last_commit: None,
+                    pull_requests: vec![
+                        get_pull_request("feature-1", "alice", vec![get_reviewer("bob", false)]),
+                        get_pull_request("feature-2", "bob", vec![get_reviewer("alice", false)]),
+                        get_pull_request("feature-3", "bob", vec![get_reviewer("alice", true)]),
+                        get_pull_request("feature-4", "carol", vec![get_reviewer("bob", true)]),
+                    ],
+                }],
+                standalone_branches: vec![],
+            }],
+        };
+
+        let user_pull_requests = get_user_pull_requests(&data, "Alice");
+
+        assert_eq!(vec!["feature-1"], branches(&user_pull_requests.authored));
+        assert_eq!(
+            vec!["feature-2"],
+            branches(&user_pull_requests.awaiting_review)
+        );
+        assert_eq!(vec!["feature-3"], branches(&user_pull_requests.reviewed));
+    }
+}
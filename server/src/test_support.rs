@@ -0,0 +1,83 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode, Uri};
+use axum::Router;
+use tokio::sync::Mutex;
+
+/// A request received by a [`StandInServer`].
+#[derive(Clone)]
+pub struct ReceivedRequest {
+    pub path: String,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+type ReceivedRequests = Arc<Mutex<Vec<ReceivedRequest>>>;
+
+/// Local stand-in for an HTTP endpoint like a webhook. It records every request it receives and
+/// fails the first one, so that retries can be tested.
+pub struct StandInServer {
+    pub url: String,
+    received: ReceivedRequests,
+}
+
+impl StandInServer {
+    pub async fn start() -> Self {
+        let received: ReceivedRequests = Arc::new(Mutex::new(Vec::new()));
+        let router = Router::new()
+            .fallback(
+                |State(received): State<ReceivedRequests>,
+                 uri: Uri,
+                 headers: HeaderMap,
+                 body: String| async move {
+                    let mut received = received.lock().await;
+                    received.push(ReceivedRequest {
+                        path: uri.path().to_string(),
+                        headers,
+                        body,
+                    });
+                    if received.len() == 1 {
+                        StatusCode::SERVICE_UNAVAILABLE
+                    } else {
+                        StatusCode::NO_CONTENT
+                    }
+                },
+            )
+            .with_state(received.clone());
+        let url = serve_locally(|_| router).await;
+        StandInServer { url, received }
+    }
+
+    pub async fn get_requests(&self, path: &str) -> Vec<ReceivedRequest> {
+        self.received
+            .lock()
+            .await
+            .iter()
+            .filter(|request| request.path == path)
+            .cloned()
+            .collect()
+    }
+
+    /// Waits up to two seconds until the given number of requests to the path have arrived.
+    pub async fn wait_for_requests(&self, path: &str, count: usize) -> Vec<ReceivedRequest> {
+        for _ in 0..100 {
+            let requests = self.get_requests(path).await;
+            if requests.len() >= count {
+                return requests;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        self.get_requests(path).await
+    }
+}
+
+/// Serves the router built for the given base url on a random local port and returns that url.
+pub async fn serve_locally(get_router: impl FnOnce(&str) -> Router) -> String {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let router = get_router(&url);
+    tokio::spawn(async move { axum::serve(listener, router).await.unwrap() });
+    url
+}
@@ -15,6 +15,8 @@ import { estimateLineCount } from './repositories/utils';
 import { getDashboardData } from './repositories/fetchDashboardData';
 import { getPRUpdates } from './repositories/fetchPRUpdates';
 import PRUpdateCard from './prupdates/pr-update-card/PRUpdateCard';
+import AlertCard from './alerts/alert-card/AlertCard';
+import { getAlerts } from './alerts/fetchAlerts';
 import {
   markAllUpdatesAsLastSeenNow,
   markUpdateAsLastSeenNow,
@@ -37,6 +39,7 @@ const RELOAD_INTERVAL_MS = 5_000;
 const App: Component = () => {
   const [dashboardData, dashboardResourceActions] =
     createResource(getDashboardData);
+  const [alerts, alertsResourceActions] = createResource(getAlerts);
   const [prUpdates, prUpdatesResourceActions] = createResource(
     prLastSeen,
     getPRUpdates,
@@ -46,6 +49,7 @@ const App: Component = () => {
   const reloadData = () => {
     prUpdatesResourceActions.refetch();
     dashboardResourceActions.refetch();
+    alertsResourceActions.refetch();
     timeout = setTimeout(reloadData, RELOAD_INTERVAL_MS);
   };
   timeout = setTimeout(reloadData, RELOAD_INTERVAL_MS);
@@ -87,6 +91,7 @@ const App: Component = () => {
                 <p>
                   Last updated:{' '}
                   {dayjs(dashboardData.last_updated_date).format('HH:mm:ss')}
+                  {dashboardData.stale && ' (stale)'}
                 </p>
               )}
               {dashboardData.currently_refreshing && <Loader />}
@@ -94,6 +99,16 @@ const App: Component = () => {
 
             <main class={styles.main}>
               <div class={styles.repositorySection}>
+                <Show when={alerts()?.length}>
+                  <div class={styles.sectionTitle}>
+                    <h2>Alerts</h2>
+                  </div>
+                  <div class={styles.alerts}>
+                    <For each={alerts()}>
+                      {(alert) => <AlertCard alert={alert} />}
+                    </For>
+                  </div>
+                </Show>
                 <div class={styles.sectionTitle}>
                   <h2>Repositories</h2>
                 </div>
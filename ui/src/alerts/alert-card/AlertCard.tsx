@@ -0,0 +1,29 @@
+import { Component } from 'solid-js';
+import styles from './AlertCard.module.css';
+import Card from '../../common/card/Card';
+import dayjs from 'dayjs';
+import { Alert } from '../../types';
+
+const AlertCard: Component<{ alert: Alert }> = (props) => {
+  return (
+    <Card>
+      <div class={styles.content}>
+        <i class="fa-solid fa-triangle-exclamation" title="Alert"></i>
+        <div class={styles.textContent}>
+          <h3>{props.alert.repository}</h3>
+          {props.alert.link ? (
+            <a href={props.alert.link} target="_blank">
+              <p>{props.alert.message}</p>
+            </a>
+          ) : (
+            <p>{props.alert.message}</p>
+          )}
+        </div>
+        <span class={styles.detail} title="Raised">
+          {dayjs(props.alert.raised_at).fromNow()}
+        </span>
+      </div>
+    </Card>
+  );
+};
+export default AlertCard;
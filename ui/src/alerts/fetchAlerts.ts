@@ -0,0 +1,12 @@
+import type { Alert } from '../types';
+
+export async function getAlerts(): Promise<Alert[]> {
+  const res = await fetch('/api/alerts');
+  const text = await res.text();
+
+  if (res.ok) {
+    return JSON.parse(text);
+  } else {
+    throw new Error(text);
+  }
+}
@@ -1,8 +1,8 @@
 import type { DashboardData } from '../types';
-import { estimateLineCount } from './utils';
+import { estimateLineCount, getDashboardApiPath } from './utils';
 
 export async function getDashboardData(): Promise<DashboardData> {
-  const res = await fetch('/api/dashboard-data');
+  const res = await fetch(`${getDashboardApiPath()}/dashboard-data`);
   const text = await res.text();
 
   if (res.ok) {
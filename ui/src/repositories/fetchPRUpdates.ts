@@ -1,5 +1,6 @@
 import { GetPullRequestUpdatesPayload, PullRequestUpdate } from '../types';
 import { PullRequestUpdateLastSeen } from '../prupdates/last-seen/storage';
+import { getDashboardApiPath } from './utils';
 
 export async function getPRUpdates(
   last_seen: PullRequestUpdateLastSeen[],
@@ -10,7 +11,7 @@ export async function getPRUpdates(
       last_seen_timestamp: item.lastSeenTimestamp,
     })),
   };
-  const res = await fetch('/api/pr-updates', {
+  const res = await fetch(`${getDashboardApiPath()}/pr-updates`, {
     method: 'POST',
     body: JSON.stringify(payload),
     headers: {
@@ -14,6 +14,14 @@ const mapToIcon = (status: PipelineStatus): string => {
       return 'circle-minus';
     case 'Queued':
       return 'circle-pause';
+    case 'Manual':
+      return 'circle-play';
+    case 'Blocked':
+      return 'circle-exclamation';
+    case 'Scheduled':
+      return 'clock';
+    case 'Skipped':
+      return 'circle-right';
     case 'None':
       return 'circle-question';
   }
@@ -31,6 +39,14 @@ const mapToColorClass = (status: PipelineStatus): string => {
       return styles.iconFailed;
     case 'Queued':
       return styles.iconDefault;
+    case 'Manual':
+      return styles.iconDefault;
+    case 'Blocked':
+      return styles.iconRunning;
+    case 'Scheduled':
+      return styles.iconDefault;
+    case 'Skipped':
+      return styles.iconDefault;
     case 'None':
       return styles.iconDefault;
   }
@@ -48,6 +64,14 @@ const mapToTitle = (status: PipelineStatus): string => {
       return 'Pipeline canceled';
     case 'Queued':
       return 'Pipeline queued';
+    case 'Manual':
+      return 'Pipeline waiting for an optional manual job';
+    case 'Blocked':
+      return 'Pipeline blocked by a manual job';
+    case 'Scheduled':
+      return 'Pipeline scheduled';
+    case 'Skipped':
+      return 'Pipeline skipped';
     case 'None':
       return 'No pipeline found';
   }
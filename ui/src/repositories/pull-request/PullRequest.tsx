@@ -36,7 +36,7 @@ const PullRequestComponent: Component<{ pullRequest: PullRequest }> = (
           title="Number of comments"
         />
         <span>{props.pullRequest.comment_count}</span>
-        {props.pullRequest.approved && (
+        {props.pullRequest.review.approved && (
           <i class="fa-solid fa-thumbs-up fa-xs" />
         )}
         <img
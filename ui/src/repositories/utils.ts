@@ -10,3 +10,12 @@ export function estimateLineCount(repository: RepositoryBranchData) {
     )
   );
 }
+
+/**
+ * Base path of the dashboard data endpoints, which is the one of a named dashboard if the page
+ * was opened with a `dashboard` query parameter (e.g. `/?dashboard=team-a`).
+ */
+export function getDashboardApiPath() {
+  const dashboard = new URLSearchParams(window.location.search).get('dashboard');
+  return dashboard ? `/api/dashboards/${encodeURIComponent(dashboard)}` : '/api';
+}
@@ -2,6 +2,7 @@ export interface DashboardData {
   last_updated_date?: string;
   repositories: RepositoryBranchData[];
   currently_refreshing: boolean;
+  stale: boolean;
 }
 
 export interface RepositoryBranchData {
@@ -15,25 +16,105 @@ export interface PullRequestTargetBranch {
   branch_name: string;
   pipeline_status: PipelineStatus;
   pipeline_url?: string;
+  pipeline_jobs: PipelineJob[];
+  pipeline_timing?: PipelineTiming;
+  last_commit?: Commit;
   pull_requests: PullRequest[];
 }
 
 export interface PullRequest {
+  number: number;
+  title: string;
+  description?: string;
+  draft: boolean;
   branch_name: string;
   user_name: string;
+  user_id: string;
   user_profile_image: string;
   comment_count: number;
+  created_date: string;
   last_activity_date: string;
-  approved: boolean;
+  review: ReviewSummary;
+  reviewers: Reviewer[];
+  ahead_behind?: AheadBehind;
+  mergeability?: Mergeability;
   pipeline_status: PipelineStatus;
   pipeline_url?: string;
+  pipeline_jobs: PipelineJob[];
+  pipeline_timing?: PipelineTiming;
   link_url: string;
 }
 
+export interface Reviewer {
+  user_id: string;
+  user_name: string;
+  approved: boolean;
+  changes_requested: boolean;
+}
+
+export interface ReviewSummary {
+  approved: boolean;
+  approvals: number;
+  approvals_required?: number;
+  changes_requested_by: string[];
+  pending_reviewers: string[];
+}
+
 export interface StandaloneBranch {
   branch_name: string;
   pipeline_status: PipelineStatus;
   pipeline_url?: string;
+  pipeline_jobs: PipelineJob[];
+  pipeline_timing?: PipelineTiming;
+  last_commit?: Commit;
+  ahead_behind?: AheadBehind;
+}
+
+export interface Mergeability {
+  can_merge: boolean;
+  has_conflicts: boolean;
+  blockers: MergeBlocker[];
+}
+
+export interface MergeBlocker {
+  kind: MergeBlockerKind;
+  message: string;
+}
+
+export type MergeBlockerKind =
+  | 'Conflicts'
+  | 'Approvals'
+  | 'UnresolvedThreads'
+  | 'Pipeline'
+  | 'Draft'
+  | 'Other';
+
+export interface AheadBehind {
+  ahead: number;
+  behind: number;
+}
+
+export interface PipelineJob {
+  name: string;
+  stage?: string;
+  status: PipelineStatus;
+  duration_seconds?: number;
+  url?: string;
+}
+
+export interface PipelineTiming {
+  commit_sha: string;
+  created_date?: string;
+  started_date?: string;
+  finished_date?: string;
+  duration_seconds?: number;
+}
+
+export interface Commit {
+  sha: string;
+  author: string;
+  message_headline: string;
+  date: string;
 }
 
 export type PipelineStatus =
@@ -42,7 +123,26 @@ export type PipelineStatus =
   | 'Failed'
   | 'None'
   | 'Canceled'
-  | 'Queued';
+  | 'Queued'
+  | 'Manual'
+  | 'Blocked'
+  | 'Scheduled'
+  | 'Skipped';
+
+export type AlertRuleType =
+  | 'TargetBranchFailed'
+  | 'PullRequestAwaitingApproval'
+  | 'StandaloneBranchWithoutPipeline';
+
+export interface Alert {
+  id?: number;
+  rule: AlertRuleType;
+  repository: string;
+  subject: string;
+  message: string;
+  link?: string;
+  raised_at: string;
+}
 
 export type PullRequestUpdateType =
   | 'Aggregated'